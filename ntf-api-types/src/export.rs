@@ -0,0 +1,370 @@
+//! Export formats for notifications.
+
+use std::io::{self, BufRead as _, BufReader, Read, Write};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Action, Format, Notification, Priority};
+
+/// A format to export notifications to.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    /// A JSON array.
+    #[default]
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Comma-separated values, with a header.
+    ///
+    /// The tags and the actions of the notifications are JSON arrays.
+    Csv,
+}
+
+/// The query parameters for `GET /export` and `POST /import`.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct ExportQuery {
+    /// The format of the body.
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+/// Writes notifications in an export format, one at a time.
+#[derive(Debug)]
+pub struct Exporter<W: Write> {
+    /// The underlying writer.
+    sink: Sink<W>,
+}
+
+/// The underlying writer of an [`Exporter`].
+#[derive(Debug)]
+enum Sink<W: Write> {
+    /// A writer for a JSON array.
+    Json {
+        /// The writer.
+        writer: W,
+        /// Whether no notification has been written yet.
+        empty: bool,
+    },
+    /// A writer for NDJSON.
+    Ndjson(W),
+    /// A writer for CSV.
+    Csv(Box<csv::Writer<W>>),
+}
+
+/// A notification as a CSV record.
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRecord {
+    /// The notification ID.
+    id: usize,
+    /// The message to show.
+    message: String,
+    /// The format of the message.
+    #[serde(default)]
+    format: Format,
+    /// Has the notification been acknowledged?
+    ack: bool,
+    /// The tags of the notification.
+    #[serde(with = "json_cell")]
+    tags: Vec<String>,
+    /// The priority of the notification.
+    #[serde(default)]
+    priority: Priority,
+    /// When the notification has been created.
+    created_at: DateTime<Utc>,
+    /// When the notification has been acknowledged, if it has.
+    acked_at: Option<DateTime<Utc>>,
+    /// When the notification has been deleted, if it has.
+    #[serde(default)]
+    deleted_at: Option<DateTime<Utc>>,
+    /// The actions of the notification.
+    #[serde(default, with = "json_cell")]
+    actions: Vec<Action>,
+}
+
+/// Errors that can occur when reading or writing an export.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// An I/O error has occurred.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The JSON is invalid.
+    #[error("invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The CSV is invalid.
+    #[error("invalid CSV: {0}")]
+    Csv(#[from] csv::Error),
+}
+
+impl ExportFormat {
+    /// Guesses the format from a file extension.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "json" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "csv" => Some(Self::Csv),
+            _ => None,
+        }
+    }
+
+    /// Returns the MIME type of the format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Ndjson => "application/x-ndjson",
+            Self::Csv => "text/csv",
+        }
+    }
+}
+
+impl<W: Write> Exporter<W> {
+    /// Creates an exporter writing to `writer`.
+    pub fn new(format: ExportFormat, writer: W) -> Self {
+        let sink = match format {
+            ExportFormat::Json => Sink::Json {
+                writer,
+                empty: true,
+            },
+            ExportFormat::Ndjson => Sink::Ndjson(writer),
+            ExportFormat::Csv => {
+                Sink::Csv(Box::new(csv::Writer::from_writer(writer)))
+            }
+        };
+
+        Self { sink }
+    }
+
+    /// Writes a notification.
+    pub fn write(
+        &mut self,
+        notification: &Notification,
+    ) -> Result<(), ExportError> {
+        match &mut self.sink {
+            Sink::Json { writer, empty } => {
+                writer.write_all(if *empty { b"[\n" } else { b",\n" })?;
+                serde_json::to_writer(&mut *writer, notification)?;
+                *empty = false;
+            }
+            Sink::Ndjson(writer) => {
+                serde_json::to_writer(&mut *writer, notification)?;
+                writer.write_all(b"\n")?;
+            }
+            Sink::Csv(writer) => {
+                writer.serialize(CsvRecord::from(notification.clone()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finishes the export and returns the underlying writer.
+    pub fn finish(self) -> Result<W, ExportError> {
+        let mut writer = match self.sink {
+            Sink::Json { mut writer, empty } => {
+                writer.write_all(if empty { b"[]\n" } else { b"\n]\n" })?;
+                writer
+            }
+            Sink::Ndjson(writer) => writer,
+            Sink::Csv(writer) => writer
+                .into_inner()
+                .map_err(|error| ExportError::Io(error.into_error()))?,
+        };
+
+        writer.flush()?;
+        Ok(writer)
+    }
+}
+
+/// Writes notifications in an export format.
+pub fn write(
+    format: ExportFormat,
+    notifications: &[Notification],
+    writer: impl Write,
+) -> Result<(), ExportError> {
+    let mut exporter = Exporter::new(format, writer);
+
+    for notification in notifications {
+        exporter.write(notification)?;
+    }
+
+    exporter.finish()?;
+    Ok(())
+}
+
+/// Reads notifications from an export format.
+pub fn read(
+    format: ExportFormat,
+    reader: impl Read,
+) -> Result<Vec<Notification>, ExportError> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::from_reader(reader)?),
+        ExportFormat::Ndjson => BufReader::new(reader)
+            .lines()
+            .filter(|line| {
+                line.as_ref().is_ok_and(|line| !line.trim().is_empty())
+            })
+            .map(|line| Ok(serde_json::from_str(&line?)?))
+            .collect(),
+        ExportFormat::Csv => csv::Reader::from_reader(reader)
+            .deserialize::<CsvRecord>()
+            .map(|record| Ok(record?.into()))
+            .collect(),
+    }
+}
+
+impl From<Notification> for CsvRecord {
+    fn from(notification: Notification) -> Self {
+        Self {
+            id: notification.id,
+            message: notification.message,
+            format: notification.format,
+            ack: notification.ack,
+            tags: notification.tags,
+            priority: notification.priority,
+            created_at: notification.created_at,
+            acked_at: notification.acked_at,
+            deleted_at: notification.deleted_at,
+            actions: notification.actions,
+        }
+    }
+}
+
+impl From<CsvRecord> for Notification {
+    fn from(record: CsvRecord) -> Self {
+        Self {
+            id: record.id,
+            message: record.message,
+            format: record.format,
+            ack: record.ack,
+            tags: record.tags,
+            priority: record.priority,
+            created_at: record.created_at,
+            acked_at: record.acked_at,
+            deleted_at: record.deleted_at,
+            actions: record.actions,
+        }
+    }
+}
+
+/// Stores a value as JSON in a CSV cell, an empty cell being the default.
+mod json_cell {
+    use serde::{
+        Deserialize as _, Deserializer, Serialize, Serializer, de,
+        de::DeserializeOwned, ser,
+    };
+
+    /// Serializes a value as a JSON string.
+    pub fn serialize<S: Serializer>(
+        value: &impl Serialize,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let json = serde_json::to_string(value).map_err(ser::Error::custom)?;
+        serializer.serialize_str(&json)
+    }
+
+    /// Deserializes a value from a JSON string.
+    pub fn deserialize<
+        'de,
+        D: Deserializer<'de>,
+        T: DeserializeOwned + Default,
+    >(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let json = String::deserialize(deserializer)?;
+
+        if json.is_empty() {
+            return Ok(T::default());
+        }
+
+        serde_json::from_str(&json).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use crate::ActionKind;
+
+    use super::*;
+
+    fn notifications() -> Vec<Notification> {
+        vec![
+            Notification {
+                id: 1,
+                message: "The disk is full, again.".to_owned(),
+                format: Format::Markdown,
+                ack: true,
+                tags: vec!["disk".to_owned(), "db1,db2".to_owned()],
+                priority: Priority::High,
+                created_at: "2025-01-02T03:04:05Z".parse().unwrap(),
+                acked_at: Some("2025-01-02T04:00:00Z".parse().unwrap()),
+                deleted_at: Some("2025-01-02T05:00:00Z".parse().unwrap()),
+                actions: vec![Action {
+                    name: "clean".to_owned(),
+                    label: "Clean, \"now\"".to_owned(),
+                    kind: ActionKind::Webhook {
+                        url: "https://example.com/clean".to_owned(),
+                        body: Some("{\"disk\": 1}".to_owned()),
+                    },
+                    outcome: None,
+                }],
+            },
+            Notification {
+                created_at: "2025-01-03T00:00:00Z".parse().unwrap(),
+                ..Notification::new(2, "Backup done.")
+            },
+        ]
+    }
+
+    fn round_trip(format: ExportFormat) -> Vec<Notification> {
+        let mut buffer = Vec::new();
+        write(format, &notifications(), &mut buffer).unwrap();
+        read(format, buffer.as_slice()).unwrap()
+    }
+
+    fn assert_same(actual: &[Notification], expected: &[Notification]) {
+        assert_eq!(
+            format!("{actual:?}"),
+            format!("{expected:?}"),
+            "the notifications differ"
+        );
+    }
+
+    #[test]
+    fn json_round_trips() {
+        assert_same(&round_trip(ExportFormat::Json), &notifications());
+    }
+
+    #[test]
+    fn ndjson_round_trips() {
+        assert_same(&round_trip(ExportFormat::Ndjson), &notifications());
+    }
+
+    #[test]
+    fn csv_round_trips() {
+        assert_same(&round_trip(ExportFormat::Csv), &notifications());
+    }
+
+    #[test]
+    fn csv_reads_missing_columns_as_empty() {
+        let csv = "id,message,format,ack,tags,priority,created_at,acked_at\n\
+                   2,Backup done.,plain,false,[],normal,\
+                   2025-01-03T00:00:00Z,\n";
+        let imported = read(ExportFormat::Csv, csv.as_bytes()).unwrap();
+
+        assert_same(&imported, &notifications().split_off(1));
+    }
+
+    #[test]
+    fn empty_json_export_is_an_empty_array() {
+        let mut buffer = Vec::new();
+        write(ExportFormat::Json, &[], &mut buffer).unwrap();
+
+        assert_eq!(buffer, b"[]\n");
+    }
+}
@@ -1,12 +1,18 @@
 //! Types for the notification API.
 
+#[cfg(feature = "export")]
+pub mod export;
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[cfg(feature = "axum")]
 use axum::{
-    extract::rejection::JsonRejection,
-    http::StatusCode,
+    extract::rejection::{BytesRejection, JsonRejection, QueryRejection},
+    http::{HeaderValue, StatusCode, header},
     response::{IntoResponse, Json, Response},
 };
 
@@ -17,8 +23,39 @@ pub struct Notification {
     pub id: usize,
     /// The message to show.
     pub message: String,
+    /// The format of the message.
+    #[serde(default)]
+    pub format: Format,
     /// Has the notification been acknowledged?
     pub ack: bool,
+    /// The tags of the notification.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The priority of the notification.
+    #[serde(default)]
+    pub priority: Priority,
+    /// When the notification has been created.
+    #[serde(default)]
+    pub created_at: DateTime<Utc>,
+    /// When the notification has been acknowledged, if it has.
+    #[serde(default)]
+    pub acked_at: Option<DateTime<Utc>>,
+    /// When the notification has been moved to the trash, if it has.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// The actions that can be run from the notification.
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+/// The query parameters for `GET /notifications`.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct ListQuery {
+    /// The number of notifications to skip.
+    #[serde(default)]
+    pub offset: usize,
+    /// The maximum number of notifications to return.
+    pub limit: Option<usize>,
 }
 
 /// The request payload for `POST /notifications`.
@@ -26,8 +63,117 @@ pub struct Notification {
 pub struct CreateNotificationPayload {
     /// The message to show.
     pub message: String,
+    /// The format of the message.
+    #[serde(default)]
+    pub format: Format,
+    /// The tags of the notification.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// The priority of the notification.
+    #[serde(default)]
+    pub priority: Priority,
+    /// The actions that can be run from the notification.
+    #[serde(default)]
+    pub actions: Vec<Action>,
+}
+
+/// An action that can be run from a notification, shown as a button.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Action {
+    /// The name of the action, unique in the notification.
+    pub name: String,
+    /// The label of the button.
+    pub label: String,
+    /// What the action does.
+    #[serde(flatten)]
+    pub kind: ActionKind,
+    /// The outcome of the last run, if any.
+    #[serde(default)]
+    pub outcome: Option<ActionOutcome>,
+}
+
+/// What an action does.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActionKind {
+    /// Opens a URL, from the client.
+    Url {
+        /// The URL to open.
+        url: String,
+    },
+    /// Calls a webhook with `POST`, from the server.
+    Webhook {
+        /// The URL of the webhook.
+        url: String,
+        /// The body of the request, if any.
+        #[serde(default)]
+        body: Option<String>,
+    },
+}
+
+/// The outcome of running an action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionOutcome {
+    /// When the action has been run.
+    pub run_at: DateTime<Utc>,
+    /// The HTTP status returned by the webhook, if it has been reached.
+    pub status: Option<u16>,
+    /// The error that has occurred, if any.
+    pub error: Option<String>,
+}
+
+/// The priority of a notification.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// The lowest priority.
+    Min,
+    /// A low priority.
+    Low,
+    /// The default priority.
+    #[default]
+    Normal,
+    /// A high priority.
+    High,
+    /// The highest priority.
+    Urgent,
+}
+
+/// The format of the message of a notification.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Format {
+    /// Plain text.
+    #[default]
+    Plain,
+    /// Markdown, to be sanitised before rendering.
+    Markdown,
 }
 
+/// An error that can occur when parsing a [`Priority`].
+#[derive(Debug, Error)]
+#[error("invalid priority `{0}` (expected min, low, normal, high or urgent)")]
+pub struct ParsePriorityError(String);
+
+/// An error that can occur when parsing a [`Format`].
+#[derive(Debug, Error)]
+#[error("invalid format `{0}` (expected plain or markdown)")]
+pub struct ParseFormatError(String);
+
 /// The reply payload for `POST /notifications`.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -38,6 +184,16 @@ pub enum CreateNotificationResult {
     Error(CreateNotificationError),
 }
 
+/// The reply payload for `POST /import`.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportNotificationsResult {
+    /// The imported notifications, with their new IDs.
+    Notifications(Vec<Notification>),
+    /// An error has occurred.
+    Error(CreateNotificationError),
+}
+
 /// Errors that can occur when creating a notification.
 #[derive(Debug, Error, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -45,6 +201,100 @@ pub enum CreateNotificationError {
     /// The payload is invalid.
     #[error("invalid payload: {0}")]
     PayloadError(String),
+    /// Some fields of the payload are invalid.
+    #[error("invalid payload: {}", ValidationError::join(.0))]
+    ValidationErrors(Vec<ValidationError>),
+    /// The request body is too large.
+    #[error("the request body is too large")]
+    PayloadTooLarge,
+    /// The signature of the request body is missing or invalid.
+    #[error("the signature is missing or invalid")]
+    InvalidSignature,
+    /// Too many notifications have been created by the client recently.
+    #[error("too many requests, retry in {retry_after} seconds")]
+    RateLimited {
+        /// The number of seconds to wait before retrying.
+        retry_after: u64,
+    },
+}
+
+/// An invalid field of a payload.
+#[derive(Debug, Clone, PartialEq, Eq, Error, Serialize, Deserialize)]
+#[error("{field}: {message}")]
+pub struct ValidationError {
+    /// The path of the field, such as `message` or `actions[0].url`.
+    pub field: String,
+    /// The kind of error.
+    pub code: ValidationErrorCode,
+    /// The description of the error.
+    pub message: String,
+}
+
+/// The kind of a validation error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationErrorCode {
+    /// A required field is missing.
+    Missing,
+    /// The field has the wrong type.
+    InvalidType,
+    /// The value is not an accepted one, such as an unknown priority.
+    InvalidValue,
+    /// The value is empty.
+    Empty,
+    /// The value is too long.
+    TooLong,
+    /// The value is already used by another item.
+    Duplicate,
+    /// An error unknown to this version of the API.
+    #[serde(other)]
+    Unknown,
+}
+
+impl Notification {
+    /// Builds an unacknowledged notification, with the default format and
+    /// priority, and without tags nor actions.
+    ///
+    /// Its creation time is left unset, i.e. the Unix epoch, as when missing
+    /// from a payload.
+    pub fn new(id: usize, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            message: message.into(),
+            format: Format::default(),
+            ack: false,
+            tags: Vec::new(),
+            priority: Priority::default(),
+            created_at: DateTime::default(),
+            acked_at: None,
+            deleted_at: None,
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl ValidationError {
+    /// Builds a validation error.
+    pub fn new(
+        field: impl Into<String>,
+        code: ValidationErrorCode,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            field: field.into(),
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Joins validation errors in a single line.
+    pub fn join(errors: &[Self]) -> String {
+        errors
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
 }
 
 /// The reply payload for `* /notifications/{id}`.
@@ -57,6 +307,85 @@ pub enum NotificationResult {
     Error(ResourceError),
 }
 
+/// An event sent by `GET /events` when a notification changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", content = "notification", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// A notification has been created.
+    Created(Notification),
+    /// A notification has been acknowledged.
+    Acked(Notification),
+    /// A notification has been deleted.
+    Deleted(Notification),
+    /// A notification has been restored from the trash.
+    Restored(Notification),
+}
+
+/// The kind of a [`NotificationEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// A notification has been created.
+    Created,
+    /// A notification has been acknowledged.
+    Acked,
+    /// A notification has been deleted.
+    Deleted,
+    /// A notification has been restored from the trash.
+    Restored,
+}
+
+/// A webhook called on notification events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    /// The webhook ID.
+    pub id: usize,
+    /// The URL to send the events to, with `POST`.
+    pub url: String,
+    /// The kinds of events to send, or all of them if empty.
+    pub events: Vec<EventKind>,
+    /// Whether the payloads are signed with a secret.
+    pub signed: bool,
+}
+
+/// The request payload for `POST /webhooks` and `PUT /webhooks/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    /// The URL to send the events to, with `POST`.
+    pub url: String,
+    /// The kinds of events to send, or all of them if empty.
+    #[serde(default)]
+    pub events: Vec<EventKind>,
+    /// The secret to sign the payloads with, if any.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// An event that could not be delivered to a webhook.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// The ID of the webhook.
+    pub webhook_id: usize,
+    /// The event that could not be delivered.
+    pub event: NotificationEvent,
+    /// The number of attempts made.
+    pub attempts: u32,
+    /// The error of the last attempt.
+    pub error: String,
+    /// When the last attempt has failed.
+    pub failed_at: DateTime<Utc>,
+}
+
+/// The reply payload for `* /webhooks/{id}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookResult {
+    /// The webhook in case of success.
+    Webhook(Webhook),
+    /// An error has occurred.
+    Error(ResourceError),
+}
+
 /// Errors that can occur when operating on a given resource.
 #[derive(Debug, Error, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -67,11 +396,142 @@ pub enum ResourceError {
         /// The ID of the missing resource.
         id: usize,
     },
+    /// The notification has no action with the given name.
+    #[error("action `{name}` not found on notification {id}")]
+    ActionNotFound {
+        /// The ID of the notification.
+        id: usize,
+        /// The name of the missing action.
+        name: String,
+    },
+}
+
+impl Priority {
+    /// All the priorities, from the lowest to the highest.
+    pub const ALL: [Self; 5] =
+        [Self::Min, Self::Low, Self::Normal, Self::High, Self::Urgent];
+
+    /// Returns the name of the priority.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Min => "min",
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+            Self::Urgent => "urgent",
+        }
+    }
+
+    /// Returns whether the priority is lower than the default one.
+    pub fn is_low(self) -> bool {
+        self < Self::Normal
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Priority {
+    type Err = ParsePriorityError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|priority| priority.name() == s)
+            .ok_or_else(|| ParsePriorityError(s.to_owned()))
+    }
+}
+
+impl Format {
+    /// All the formats.
+    pub const ALL: [Self; 2] = [Self::Plain, Self::Markdown];
+
+    /// Returns the name of the format.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Markdown => "markdown",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = ParseFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| ParseFormatError(s.to_owned()))
+    }
+}
+
+impl ActionOutcome {
+    /// Returns whether the action has succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+            && self
+                .status
+                .is_none_or(|status| (200..300).contains(&status))
+    }
+}
+
+impl NotificationEvent {
+    /// Returns the kind of the event.
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::Created(_) => EventKind::Created,
+            Self::Acked(_) => EventKind::Acked,
+            Self::Deleted(_) => EventKind::Deleted,
+            Self::Restored(_) => EventKind::Restored,
+        }
+    }
+
+    /// Returns the notification concerned by the event.
+    pub fn notification(&self) -> &Notification {
+        match self {
+            Self::Created(notification)
+            | Self::Acked(notification)
+            | Self::Deleted(notification)
+            | Self::Restored(notification) => notification,
+        }
+    }
 }
 
 #[cfg(feature = "axum")]
 impl From<JsonRejection> for CreateNotificationError {
     fn from(value: JsonRejection) -> Self {
+        if value.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            Self::PayloadTooLarge
+        } else {
+            Self::PayloadError(value.to_string())
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl From<BytesRejection> for CreateNotificationError {
+    fn from(value: BytesRejection) -> Self {
+        if value.status() == StatusCode::PAYLOAD_TOO_LARGE {
+            Self::PayloadTooLarge
+        } else {
+            Self::PayloadError(value.to_string())
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+impl From<QueryRejection> for CreateNotificationError {
+    fn from(value: QueryRejection) -> Self {
         Self::PayloadError(value.to_string())
     }
 }
@@ -83,14 +543,38 @@ impl IntoResponse for Notification {
     }
 }
 
+#[cfg(feature = "axum")]
+impl IntoResponse for Webhook {
+    fn into_response(self) -> Response {
+        Json(WebhookResult::Webhook(self)).into_response()
+    }
+}
+
 #[cfg(feature = "axum")]
 impl IntoResponse for CreateNotificationError {
     fn into_response(self) -> Response {
         let status = match self {
             Self::PayloadError(_) => StatusCode::BAD_REQUEST,
+            Self::ValidationErrors(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::InvalidSignature => StatusCode::UNAUTHORIZED,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+        };
+        let retry_after = match self {
+            Self::RateLimited { retry_after } => Some(retry_after),
+            _ => None,
         };
 
-        (status, Json(CreateNotificationResult::Error(self))).into_response()
+        let mut response =
+            (status, Json(CreateNotificationResult::Error(self)))
+                .into_response();
+        if let Some(retry_after) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+
+        response
     }
 }
 
@@ -98,7 +582,9 @@ impl IntoResponse for CreateNotificationError {
 impl IntoResponse for ResourceError {
     fn into_response(self) -> Response {
         let status = match self {
-            Self::NotFound { .. } => StatusCode::NOT_FOUND,
+            Self::NotFound { .. } | Self::ActionNotFound { .. } => {
+                StatusCode::NOT_FOUND
+            }
         };
 
         (status, Json(NotificationResult::Error(self))).into_response()
@@ -1,14 +1,21 @@
 //! Client library for the notification API.
 
-pub use ntf_api_types::Notification;
+pub use ntf_api_types::{
+    Action, ActionKind, ActionOutcome, CreateNotificationError,
+    CreateNotificationPayload, Format, ListQuery, Notification,
+    NotificationEvent, Priority, ValidationError, ValidationErrorCode,
+};
+
+#[cfg(feature = "export")]
+pub use ntf_api_types::export;
 
-use reqwest::{Client, Method};
+use reqwest::{Client, Method, Response, StatusCode};
 use serde_json::Value;
 use thiserror::Error;
 
 use ntf_api_types::{
-    CreateNotificationError, CreateNotificationPayload,
-    CreateNotificationResult, NotificationResult, ResourceError,
+    CreateNotificationResult, ImportNotificationsResult, NotificationResult,
+    ResourceError,
 };
 use ntf_poc_helpers::tracing::LogResult as _;
 
@@ -17,6 +24,8 @@ use ntf_poc_helpers::tracing::LogResult as _;
 pub struct ApiClient {
     /// The base URL of the API.
     base_url: String,
+    /// The token to authenticate with, if any.
+    token: Option<String>,
     /// The reqwest client.
     client: Client,
 }
@@ -35,6 +44,23 @@ pub enum CreateError {
     /// An error occurred during the API request.
     #[error(transparent)]
     ApiError(ApiError),
+    /// The server has rejected the payload.
+    #[error(transparent)]
+    PayloadError(CreateNotificationError),
+    /// The server has rejected some fields of the payload.
+    #[error("invalid payload: {}", ValidationError::join(.0))]
+    ValidationErrors(Vec<ValidationError>),
+}
+
+/// Errors that can occur when watching notification events.
+#[derive(Debug, Error)]
+pub enum WatchError {
+    /// An error occurred during the API request.
+    #[error(transparent)]
+    ApiError(ApiError),
+    /// The server does not support pushing events.
+    #[error("the server does not support pushing events")]
+    Unsupported,
 }
 
 /// Errors that can occur when getting a notification.
@@ -46,6 +72,12 @@ pub type AckError = ResourceAccessError;
 /// Errors that can occur when deleting a notification.
 pub type DeleteError = ResourceAccessError;
 
+/// Errors that can occur when restoring a notification from the trash.
+pub type RestoreError = ResourceAccessError;
+
+/// Errors that can occur when running an action of a notification.
+pub type RunActionError = ResourceAccessError;
+
 /// Errors that can occur when accessing a resource.
 #[derive(Debug, Error)]
 pub enum ResourceAccessError {
@@ -58,11 +90,30 @@ pub enum ResourceAccessError {
         /// The ID of the missing resource.
         id: usize,
     },
+    /// The notification has no action with the given name.
+    #[error("the notification has no action `{name}` (id = {id}).")]
+    ActionNotFound {
+        /// The ID of the notification.
+        id: usize,
+        /// The name of the missing action.
+        name: String,
+    },
 }
 
 /// Errors that can occur when making API calls.
 #[derive(Debug, Error)]
 pub enum ApiError {
+    /// The server could not be reached.
+    #[error("could not connect to the server at {base_url}")]
+    ConnectionError {
+        /// The base URL of the API.
+        base_url: String,
+        /// The source of the error.
+        source: BoxedError,
+    },
+    /// The server has refused the request for lack of valid credentials.
+    #[error("the server has refused the request as unauthorised")]
+    Unauthorised,
     /// An error occurend during the API request.
     #[error("an error occurred during the API request")]
     RequestError(#[source] BoxedError),
@@ -79,22 +130,44 @@ impl ApiClient {
     pub fn new(base_url: &str) -> Self {
         Self {
             base_url: base_url.to_owned(),
+            token: None,
             client: Client::new(),
         }
     }
 
+    /// Sets the token to authenticate with.
+    #[must_use]
+    pub fn with_token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    /// Checks that the server is up and running.
+    pub async fn status(&self) -> Result<(), ApiError> {
+        self.request(Method::GET, "status", None).await.map(drop)
+    }
+
     /// Lists the notifications.
     pub async fn list_notifications(
         &self,
     ) -> Result<Vec<Notification>, ListError> {
-        let value = self
-            .request(Method::GET, "notifications", None)
-            .await
-            .map_err(ListError::ApiError)?;
+        self.list_notifications_page(ListQuery::default()).await
+    }
 
-        serde_json::from_value(value)
-            .wrap_err_with_type_info()
-            .map_err(ListError::deserialisation_error)
+    /// Lists a page of the notifications.
+    pub async fn list_notifications_page(
+        &self,
+        query: ListQuery,
+    ) -> Result<Vec<Notification>, ListError> {
+        self.list("notifications", query).await
+    }
+
+    /// Lists a page of the notifications in the trash.
+    pub async fn list_trash(
+        &self,
+        query: ListQuery,
+    ) -> Result<Vec<Notification>, ListError> {
+        self.list("trash", query).await
     }
 
     /// Creates a notification.
@@ -104,16 +177,14 @@ impl ApiClient {
     )]
     pub async fn create_notification(
         &self,
-        message: &str,
+        payload: &CreateNotificationPayload,
     ) -> Result<Notification, CreateError> {
         #[expect(
             clippy::expect_used,
             reason = "the payload is known to be serialisable to JSON"
         )]
-        let body = serde_json::to_value(CreateNotificationPayload {
-            message: message.to_owned(),
-        })
-        .expect("could not serialise to JSON");
+        let body =
+            serde_json::to_value(payload).expect("could not serialise to JSON");
 
         let response = self
             .request(Method::POST, "notifications", Some(&body))
@@ -134,12 +205,51 @@ impl ApiClient {
         }
     }
 
+    /// Imports notifications, preserving their acknowledgement state and
+    /// timestamps.
+    ///
+    /// The server assigns new IDs to the imported notifications.
+    #[expect(
+        clippy::missing_panics_doc,
+        reason = "cannot actually panic (see reason on expect_used below)"
+    )]
+    pub async fn import_notifications(
+        &self,
+        notifications: &[Notification],
+    ) -> Result<Vec<Notification>, CreateError> {
+        #[expect(
+            clippy::expect_used,
+            reason = "notifications are known to be serialisable to JSON"
+        )]
+        let body = serde_json::to_value(notifications)
+            .expect("could not serialise to JSON");
+
+        let response = self
+            .request(Method::POST, "import", Some(&body))
+            .await
+            .map_err(CreateError::ApiError)?;
+
+        let response = serde_json::from_value(response)
+            .wrap_err_with_type_info()
+            .map_err(CreateError::deserialisation_error)?;
+
+        match response {
+            ImportNotificationsResult::Notifications(notifications) => {
+                Ok(notifications)
+            }
+            ImportNotificationsResult::Error(error) => {
+                Err(CreateError::payload_error(error))
+            }
+        }
+    }
+
     /// Gets a notification by its ID.
     pub async fn get_notification(
         &self,
         id: usize,
     ) -> Result<Notification, GetError> {
-        self.request_notification(id, Method::GET).await
+        self.request_notification(Method::GET, &format!("notifications/{id}"))
+            .await
     }
 
     /// Acknowledges a notification by its ID.
@@ -147,7 +257,8 @@ impl ApiClient {
         &self,
         id: usize,
     ) -> Result<Notification, AckError> {
-        self.request_notification(id, Method::PUT).await
+        self.request_notification(Method::PUT, &format!("notifications/{id}"))
+            .await
     }
 
     /// Deletes a notification by its ID.
@@ -155,17 +266,91 @@ impl ApiClient {
         &self,
         id: usize,
     ) -> Result<Notification, DeleteError> {
-        self.request_notification(id, Method::DELETE).await
+        self.request_notification(
+            Method::DELETE,
+            &format!("notifications/{id}"),
+        )
+        .await
     }
 
-    /// Requests a notification by its ID with the given `method`.
-    async fn request_notification(
+    /// Restores a notification from the trash by its ID.
+    pub async fn restore_notification(
+        &self,
+        id: usize,
+    ) -> Result<Notification, RestoreError> {
+        self.request_notification(
+            Method::POST,
+            &format!("notifications/{id}/restore"),
+        )
+        .await
+    }
+
+    /// Runs an action of a notification on the server, and returns the
+    /// notification with the outcome of the action.
+    pub async fn run_action(
         &self,
         id: usize,
+        name: &str,
+    ) -> Result<Notification, RunActionError> {
+        self.request_notification(
+            Method::POST,
+            &format!("notifications/{id}/actions/{name}"),
+        )
+        .await
+    }
+
+    /// Watches the notification events pushed by the server.
+    ///
+    /// Returns [`WatchError::Unsupported`] if the server does not provide the
+    /// `GET /events` route.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn watch_notifications(&self) -> Result<EventStream, WatchError> {
+        let response = self
+            .send(Method::GET, "events", None)
+            .await
+            .map_err(WatchError::ApiError)?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => {
+                Err(WatchError::Unsupported)
+            }
+            _ => Ok(EventStream {
+                response,
+                buffer: Vec::new(),
+            }),
+        }
+    }
+
+    /// Lists a page of the notifications on the given route.
+    async fn list(
+        &self,
+        route: &str,
+        query: ListQuery,
+    ) -> Result<Vec<Notification>, ListError> {
+        let ListQuery { offset, limit } = query;
+        let route = match limit {
+            Some(limit) => format!("{route}?offset={offset}&limit={limit}"),
+            None => format!("{route}?offset={offset}"),
+        };
+
+        let value = self
+            .request(Method::GET, &route, None)
+            .await
+            .map_err(ListError::ApiError)?;
+
+        serde_json::from_value(value)
+            .wrap_err_with_type_info()
+            .map_err(ListError::deserialisation_error)
+    }
+
+    /// Requests a notification on the given route with the given `method`.
+    async fn request_notification(
+        &self,
         method: Method,
+        route: &str,
     ) -> Result<Notification, ResourceAccessError> {
         let value = self
-            .request(method, &format!("notifications/{id}"), None)
+            .request(method, route, None)
             .await
             .map_err(ResourceAccessError::ApiError)?;
 
@@ -179,6 +364,9 @@ impl ApiClient {
                 ResourceError::NotFound { id } => {
                     Err(ResourceAccessError::NotFound { id })
                 }
+                ResourceError::ActionNotFound { id, name } => {
+                    Err(ResourceAccessError::ActionNotFound { id, name })
+                }
             },
         }
     }
@@ -190,18 +378,130 @@ impl ApiClient {
         route: &str,
         body: Option<&Value>,
     ) -> Result<Value, ApiError> {
-        self.client
-            .request(method, format!("{}/{}", self.base_url, route))
-            .json(&body)
-            .send()
-            .await
-            .map_err(ApiError::request_error)
-            .log_err()?
+        self.send(method, route, body)
+            .await?
             .json()
             .await
             .map_err(ApiError::response_error)
             .log_err()
     }
+
+    /// Sends a request on the given route and returns the raw response.
+    async fn send(
+        &self,
+        method: Method,
+        route: &str,
+        body: Option<&Value>,
+    ) -> Result<Response, ApiError> {
+        let mut request = self
+            .client
+            .request(method, format!("{}/{}", self.base_url, route))
+            .json(&body);
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|error| ApiError::request_error(error, &self.base_url))
+            .log_err()?;
+
+        if matches!(
+            response.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Err(ApiError::Unauthorised).log_err();
+        }
+
+        Ok(response)
+    }
+}
+
+/// A stream of notification events pushed by the server.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+pub struct EventStream {
+    /// The streaming response from the server.
+    response: Response,
+    /// The data received but not parsed yet.
+    buffer: Vec<u8>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl EventStream {
+    /// Waits for the next event.
+    ///
+    /// Returns `None` when the server closes the stream.
+    pub async fn next(
+        &mut self,
+    ) -> Option<Result<NotificationEvent, ApiError>> {
+        loop {
+            if let Some(message) = self.next_message() {
+                match parse_event(&message) {
+                    Some(result) => return Some(result),
+                    None => continue,
+                }
+            }
+
+            match self.response.chunk().await {
+                Ok(Some(chunk)) => self.buffer.extend_from_slice(&chunk),
+                Ok(None) => return None,
+                Err(error) => {
+                    return Some(
+                        Err(ApiError::response_error(error)).log_err(),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Extracts the next complete message from the buffer, if any.
+    fn next_message(&mut self) -> Option<String> {
+        take_message(&mut self.buffer)
+    }
+}
+
+/// Extracts the first complete message from a buffer, if any.
+///
+/// Messages end with an empty line, with either `\n` or `\r\n` line endings.
+#[cfg(not(target_arch = "wasm32"))]
+fn take_message(buffer: &mut Vec<u8>) -> Option<String> {
+    let end = [b"\n\n".as_slice(), b"\r\n\r\n"]
+        .into_iter()
+        .filter_map(|separator| {
+            buffer
+                .windows(separator.len())
+                .position(|window| window == separator)
+                .map(|position| position + separator.len())
+        })
+        .min()?;
+
+    let message = buffer.drain(..end).collect::<Vec<_>>();
+    Some(String::from_utf8_lossy(&message).into_owned())
+}
+
+/// Parses a server-sent event message.
+///
+/// Returns `None` for messages without data, like keep-alive comments.
+#[cfg(not(target_arch = "wasm32"))]
+fn parse_event(message: &str) -> Option<Result<NotificationEvent, ApiError>> {
+    let data = message
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect::<Vec<_>>();
+
+    if data.is_empty() {
+        return None;
+    }
+
+    Some(
+        serde_json::from_str(&data.join("\n"))
+            .wrap_err_with_type_info()
+            .map_err(|error| ApiError::ResponseError(Box::new(error))),
+    )
 }
 
 impl ListError {
@@ -212,15 +512,21 @@ impl ListError {
 }
 
 impl CreateError {
-    /// Builds a [`CreateError::ApiError`] from a [`CreateNotificationError`].
-    fn payload_error(error: CreateNotificationError) -> Self {
-        Self::ApiError(ApiError::RequestError(Box::new(error)))
-    }
-
     /// Builds a [`CreateError::ApiError`] from a [`DeserialisationError`].
     fn deserialisation_error(error: DeserialisationError) -> Self {
         Self::ApiError(ApiError::ResponseError(Box::new(error)))
     }
+
+    /// Builds a [`CreateError`] from the error returned by the server,
+    /// exposing the validation errors.
+    fn payload_error(error: CreateNotificationError) -> Self {
+        match error {
+            CreateNotificationError::ValidationErrors(errors) => {
+                Self::ValidationErrors(errors)
+            }
+            error => Self::PayloadError(error),
+        }
+    }
 }
 
 impl ResourceAccessError {
@@ -231,9 +537,19 @@ impl ResourceAccessError {
 }
 
 impl ApiError {
-    /// Builds an [`ApiError::RequestError`] from a [`reqwest::Error`].
-    fn request_error(error: reqwest::Error) -> Self {
-        Self::RequestError(Box::new(error))
+    /// Builds an [`ApiError`] from a [`reqwest::Error`] raised by a request.
+    ///
+    /// Connection failures are reported as [`ApiError::ConnectionError`], any
+    /// other error as [`ApiError::RequestError`].
+    fn request_error(error: reqwest::Error, base_url: &str) -> Self {
+        if error.is_connect() {
+            Self::ConnectionError {
+                base_url: base_url.to_owned(),
+                source: Box::new(error),
+            }
+        } else {
+            Self::RequestError(Box::new(error))
+        }
     }
 
     /// Builds an [`ApiError::ResponseError`] from a [`reqwest::Error`].
@@ -274,3 +590,45 @@ impl<T> DeserialisationResult<T> for Result<T, serde_json::Error> {
             .log_err()
     }
 }
+
+#[cfg(test)]
+#[cfg(not(target_arch = "wasm32"))]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn parse_event_parses_the_data_of_the_message() {
+        let message = "data: {\"event\":\"created\",\"notification\":\
+            {\"id\":1,\"message\":\"hello\",\"ack\":false}}\n\n";
+
+        let event = parse_event(message).unwrap().unwrap();
+
+        assert!(matches!(
+            event,
+            NotificationEvent::Created(Notification { id: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn take_message_splits_on_empty_lines() {
+        let mut buffer = b"data: 1\n\ndata: 2\r\n\r\ndata: 3\r\n".to_vec();
+
+        assert_eq!(take_message(&mut buffer).unwrap(), "data: 1\n\n");
+        assert_eq!(take_message(&mut buffer).unwrap(), "data: 2\r\n\r\n");
+        assert_eq!(take_message(&mut buffer), None);
+        assert_eq!(buffer, b"data: 3\r\n");
+    }
+
+    #[test]
+    fn parse_event_ignores_messages_without_data() {
+        assert!(parse_event(":\n\n").is_none());
+    }
+
+    #[test]
+    fn parse_event_returns_an_error_on_invalid_data() {
+        let event = parse_event("data: {}\n\n").unwrap();
+        assert!(matches!(event, Err(ApiError::ResponseError(_))));
+    }
+}
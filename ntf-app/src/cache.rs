@@ -0,0 +1,257 @@
+//! The offline cache of the notifications and pending actions.
+//!
+//! The cache is stored in `$XDG_CACHE_HOME/ntf/app-cache.json` on native
+//! platforms, and in `IndexedDB` on the web.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use ntf_api::Notification;
+
+/// The offline cache.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Cache {
+    /// The URL of the server the notifications come from.
+    pub server_url: String,
+    /// The last fetched notifications, with the pending actions applied.
+    pub notifications: Vec<Notification>,
+    /// The actions waiting for the server to be reachable.
+    pub queue: Vec<PendingAction>,
+}
+
+/// An action waiting for the server to be reachable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", content = "id", rename_all = "snake_case")]
+pub enum PendingAction {
+    /// Acknowledge a notification.
+    Ack(usize),
+    /// Delete a notification.
+    Delete(usize),
+}
+
+impl Cache {
+    /// Loads the cache, if any.
+    pub async fn load() -> Option<Self> {
+        let contents = storage::read().await?;
+
+        serde_json::from_str(&contents)
+            .inspect_err(|error| warn!(%error, "invalid offline cache"))
+            .ok()
+    }
+
+    /// Saves the cache.
+    pub async fn save(&self) {
+        match serde_json::to_string(self) {
+            Ok(contents) => storage::write(&contents).await,
+            Err(error) => warn!(%error, "failed to serialise the cache"),
+        }
+    }
+}
+
+impl PendingAction {
+    /// Applies the action locally to a list of notifications.
+    pub fn apply(self, notifications: &mut Vec<Notification>) {
+        match self {
+            Self::Ack(id) => notifications
+                .iter_mut()
+                .filter(|ntf| ntf.id == id)
+                .for_each(|ntf| ntf.ack = true),
+            Self::Delete(id) => notifications.retain(|ntf| ntf.id != id),
+        }
+    }
+}
+
+/// The storage of the cache in a file.
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use std::{env, fs, io, path::PathBuf};
+
+    use dioxus::prelude::*;
+
+    /// Reads the cache file, if any.
+    #[expect(clippy::unused_async, reason = "asynchronous on the web")]
+    pub async fn read() -> Option<String> {
+        let path = path()?;
+
+        fs::read_to_string(&path)
+            .inspect_err(|error| {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(%error, path = %path.display(), "failed to read the cache");
+                }
+            })
+            .ok()
+    }
+
+    /// Writes the cache file.
+    #[expect(clippy::unused_async, reason = "asynchronous on the web")]
+    pub async fn write(contents: &str) {
+        let Some(path) = path() else {
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| fs::write(&path, contents));
+
+        if let Err(error) = result {
+            warn!(%error, path = %path.display(), "failed to save the cache");
+        }
+    }
+
+    /// Returns the path of the cache file.
+    ///
+    /// This is `$XDG_CACHE_HOME/ntf/app-cache.json`, where `XDG_CACHE_HOME`
+    /// defaults to `~/.cache`.
+    fn path() -> Option<PathBuf> {
+        let cache_home = env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".cache"))
+            })?;
+
+        Some(cache_home.join("ntf").join("app-cache.json"))
+    }
+}
+
+/// The storage of the cache in `IndexedDB`.
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use dioxus::prelude::*;
+    use js_sys::Promise;
+    use wasm_bindgen::{JsCast as _, JsValue, closure::Closure};
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{Event, IdbDatabase, IdbRequest, IdbTransactionMode};
+
+    /// The name of the database.
+    const DATABASE: &str = "ntf-app";
+    /// The name of the object store.
+    const STORE: &str = "cache";
+    /// The key of the cache in the object store.
+    const KEY: &str = "cache";
+
+    /// Reads the cache from `IndexedDB`, if any.
+    pub async fn read() -> Option<String> {
+        let result = async {
+            let store = database()
+                .await?
+                .transaction_with_str_and_mode(
+                    STORE,
+                    IdbTransactionMode::Readonly,
+                )?
+                .object_store(STORE)?;
+
+            wait(&store.get(&JsValue::from_str(KEY))?).await
+        };
+
+        result
+            .await
+            .inspect_err(|error| warn!(?error, "failed to read the cache"))
+            .ok()?
+            .as_string()
+    }
+
+    /// Writes the cache to `IndexedDB`.
+    pub async fn write(contents: &str) {
+        let result = async {
+            let store = database()
+                .await?
+                .transaction_with_str_and_mode(
+                    STORE,
+                    IdbTransactionMode::Readwrite,
+                )?
+                .object_store(STORE)?;
+
+            let request = store.put_with_key(
+                &JsValue::from_str(contents),
+                &JsValue::from_str(KEY),
+            )?;
+            wait(&request).await
+        };
+
+        if let Err(error) = result.await {
+            warn!(?error, "failed to save the cache");
+        }
+    }
+
+    /// Opens the database, creating the object store if needed.
+    async fn database() -> Result<IdbDatabase, JsValue> {
+        let request = web_sys::window()
+            .ok_or("no window")?
+            .indexed_db()?
+            .ok_or("IndexedDB is not available")?
+            .open_with_u32(DATABASE, 1)?;
+
+        let upgrade = Closure::<dyn FnMut(Event)>::new({
+            let request = request.clone();
+            move |_| {
+                let result = request
+                    .result()
+                    .and_then(|database| database.dyn_into::<IdbDatabase>())
+                    .and_then(|database| database.create_object_store(STORE));
+
+                if let Err(error) = result {
+                    warn!(?error, "failed to create the object store");
+                }
+            }
+        });
+        request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+
+        let database = wait(&request).await;
+        request.set_onupgradeneeded(None);
+
+        database?.dyn_into()
+    }
+
+    /// Waits for a request to complete, and returns its result.
+    async fn wait(request: &IdbRequest) -> Result<JsValue, JsValue> {
+        let promise = Promise::new(&mut |resolve, reject| {
+            request.set_onsuccess(Some(&resolve));
+            request.set_onerror(Some(&reject));
+        });
+
+        JsFuture::from(promise).await?;
+        request.result()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    fn notification(id: usize) -> Notification {
+        Notification::new(id, format!("Message {id}"))
+    }
+
+    #[test]
+    fn pending_actions_apply_locally() {
+        let mut notifications = vec![notification(1), notification(2)];
+
+        PendingAction::Ack(1).apply(&mut notifications);
+        PendingAction::Delete(2).apply(&mut notifications);
+        PendingAction::Delete(3).apply(&mut notifications);
+
+        assert_eq!(notifications.len(), 1);
+        assert!(notifications.first().unwrap().ack);
+    }
+
+    #[test]
+    fn cache_round_trips_through_json() {
+        let cache = Cache {
+            server_url: String::from("http://localhost:3000"),
+            notifications: vec![notification(1)],
+            queue: vec![PendingAction::Ack(1), PendingAction::Delete(2)],
+        };
+
+        let json = serde_json::to_string(&cache).unwrap();
+        let parsed = serde_json::from_str::<Cache>(&json).unwrap();
+
+        assert_eq!(parsed.server_url, cache.server_url);
+        assert_eq!(parsed.notifications.len(), 1);
+        assert_eq!(parsed.queue, cache.queue);
+    }
+}
@@ -0,0 +1,54 @@
+//! Integration with the desktop environment.
+
+mod notifications;
+#[cfg(any(target_os = "windows", target_os = "linux", target_os = "macos"))]
+mod tray;
+
+use dioxus::router::Navigator;
+
+use crate::Route;
+
+/// Returns the configuration of the desktop application.
+pub fn config() -> dioxus::desktop::Config {
+    let config = dioxus::desktop::Config::new();
+
+    // NOTE: Only hide the window on close when a tray icon can show it back.
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    let config = config.with_close_behaviour(
+        dioxus::desktop::WindowCloseBehaviour::WindowHides,
+    );
+
+    config
+}
+
+/// Integrates the application with the desktop environment.
+///
+/// Must be used under the router, as opening a notification navigates to it.
+pub fn use_desktop_integration() {
+    notifications::use_desktop_notifications();
+
+    #[cfg(any(
+        target_os = "windows",
+        target_os = "linux",
+        target_os = "macos"
+    ))]
+    tray::use_tray();
+}
+
+/// Shows the window.
+fn show_window() {
+    let window = dioxus::desktop::window();
+    window.set_visible(true);
+    window.set_minimized(false);
+    window.set_focus();
+}
+
+/// Shows the window and navigates to a notification.
+fn open_notification(navigator: Navigator, id: usize) {
+    show_window();
+    navigator.push(Route::Show { id });
+}
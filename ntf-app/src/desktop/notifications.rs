@@ -0,0 +1,170 @@
+//! Native desktop notifications.
+
+use std::collections::HashSet;
+
+use dioxus::{prelude::*, router::Navigator};
+#[cfg(not(target_os = "macos"))]
+use notify_rust::Urgency;
+
+#[cfg(not(target_os = "macos"))]
+use ntf_api::Priority;
+use ntf_api::{ActionKind, Notification};
+
+use crate::{
+    settings::SETTINGS,
+    state::{self, NEW_NOTIFICATIONS, NOTIFICATIONS, ack_notification},
+};
+
+use super::open_notification;
+
+/// The action to acknowledge the notification.
+const ACK_ACTION: &str = "ack";
+/// The action to open the notification.
+const OPEN_ACTION: &str = "open";
+/// The action triggered by clicking the notification itself.
+const DEFAULT_ACTION: &str = "default";
+/// The prefix of the actions attached to the notification.
+const ACTION_PREFIX: &str = "action:";
+
+/// Shows a desktop notification for each new notification.
+///
+/// Must be used under the router, as opening a notification navigates to it.
+pub(super) fn use_desktop_notifications() {
+    let navigator = use_navigator();
+    let mut notified = HashSet::new();
+
+    use_effect(move || {
+        let new = NEW_NOTIFICATIONS.read();
+
+        NOTIFICATIONS
+            .read()
+            .iter()
+            .flatten()
+            .filter(|ntf| !ntf.ack && new.contains(&ntf.id))
+            .filter(|ntf| notified.insert(ntf.id))
+            .for_each(|ntf| notify(ntf.clone(), navigator));
+    });
+}
+
+/// Shows a desktop notification, then handles the chosen action.
+fn notify(notification: Notification, navigator: Navigator) {
+    if SETTINGS.read().mute_low_priority && notification.priority.is_low() {
+        return;
+    }
+
+    let id = notification.id;
+
+    spawn(async move {
+        match tokio::task::spawn_blocking(move || show(&notification)).await {
+            Ok(Some(action)) => handle_action(&action, id, navigator).await,
+            Ok(None) => {}
+            Err(error) => {
+                warn!(%error, "failed to show a desktop notification");
+            }
+        }
+    });
+}
+
+/// Handles an action chosen from a desktop notification.
+async fn handle_action(action: &str, id: usize, navigator: Navigator) {
+    match action {
+        ACK_ACTION => ack_notification(id).await,
+        OPEN_ACTION | DEFAULT_ACTION => open_notification(navigator, id),
+        _ => {
+            if let Some(name) = action.strip_prefix(ACTION_PREFIX) {
+                run_action(id, name).await;
+            }
+        }
+    }
+}
+
+/// Runs an action attached to a notification.
+///
+/// URLs are opened in the default browser, and webhooks are run by the server.
+async fn run_action(id: usize, name: &str) {
+    let kind = NOTIFICATIONS
+        .peek()
+        .iter()
+        .flatten()
+        .find(|ntf| ntf.id == id)
+        .and_then(|ntf| ntf.actions.iter().find(|action| action.name == name))
+        .map(|action| action.kind.clone());
+
+    match kind {
+        Some(ActionKind::Url { url }) => {
+            if let Err(error) = webbrowser::open(&url) {
+                warn!(%error, url, "failed to open the URL");
+            }
+        }
+        Some(ActionKind::Webhook { .. }) => {
+            state::run_action(id, name).await;
+        }
+        None => {}
+    }
+}
+
+/// Shows a desktop notification and waits for an action to be chosen.
+///
+/// Returns `None` if the notification is closed without any action.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn show(notification: &Notification) -> Option<String> {
+    let mut desktop_notification = desktop_notification(notification);
+    desktop_notification
+        .action(DEFAULT_ACTION, "Open")
+        .action(OPEN_ACTION, "Open")
+        .action(ACK_ACTION, "Acknowledge");
+
+    for action in &notification.actions {
+        desktop_notification
+            .action(&format!("{ACTION_PREFIX}{}", action.name), &action.label);
+    }
+
+    let handle = desktop_notification
+        .show()
+        .inspect_err(|error| warn!(%error, "failed to show a notification"))
+        .ok()?;
+
+    let mut chosen = None;
+    handle.wait_for_action(|action| chosen = Some(action.to_owned()));
+    chosen
+}
+
+/// Shows a desktop notification.
+///
+/// Actions are only supported through D-Bus, so this always returns `None`.
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn show(notification: &Notification) -> Option<String> {
+    if let Err(error) = desktop_notification(notification).show() {
+        warn!(%error, "failed to show a notification");
+    }
+
+    None
+}
+
+/// Builds the desktop notification for a notification.
+fn desktop_notification(
+    notification: &Notification,
+) -> notify_rust::Notification {
+    let mut desktop_notification = notify_rust::Notification::new();
+
+    desktop_notification
+        .appname("ntf-app")
+        .summary(&format!("Notification #{}", notification.id))
+        .body(&notification.message);
+
+    // NOTE: Urgencies are not supported by notify-rust on macOS.
+    #[cfg(not(target_os = "macos"))]
+    desktop_notification.urgency(urgency(notification.priority));
+
+    desktop_notification
+}
+
+/// Returns the urgency of the desktop notification for a priority.
+#[cfg(not(target_os = "macos"))]
+fn urgency(priority: Priority) -> Urgency {
+    match priority {
+        Priority::Min | Priority::Low => Urgency::Low,
+        Priority::Normal | Priority::High => Urgency::Normal,
+        Priority::Urgent => Urgency::Critical,
+    }
+}
@@ -0,0 +1,256 @@
+//! The system tray icon.
+
+use dioxus::desktop::{
+    WindowCloseBehaviour,
+    trayicon::{
+        Icon, TrayIcon, init_tray_icon,
+        menu::{self, Menu, MenuItem, PredefinedMenuItem},
+    },
+    use_tray_menu_event_handler,
+};
+use dioxus::prelude::*;
+
+use ntf_api::Notification;
+
+use crate::state::{NOTIFICATIONS, ack_notification};
+
+use super::{open_notification, show_window};
+
+/// The number of notifications listed in the menu.
+const LATEST_COUNT: usize = 5;
+/// The maximum number of characters of a message in the menu.
+const LABEL_LENGTH: usize = 40;
+
+/// The ID of the menu item showing the window.
+const SHOW_ITEM: &str = "show";
+/// The prefix of the ID of the menu items opening a notification.
+const OPEN_ITEM_PREFIX: &str = "open:";
+/// The ID of the menu item acknowledging all the notifications.
+const ACK_ALL_ITEM: &str = "ack-all";
+/// The ID of the menu item quitting the application.
+const QUIT_ITEM: &str = "quit";
+
+/// The width and height of the icon, in pixels.
+const ICON_SIZE: usize = 32;
+/// The scale of the digits drawn on the icon.
+const GLYPH_SCALE: usize = 4;
+/// The width of a glyph, before scaling.
+const GLYPH_WIDTH: usize = 3;
+/// The height of a glyph, before scaling.
+const GLYPH_HEIGHT: usize = 5;
+/// The colour of the icon when all notifications are acknowledged.
+const IDLE_COLOUR: [u8; 4] = [0x80, 0x80, 0x80, 0xff];
+/// The colour of the icon when some notifications are unacknowledged.
+const UNACKED_COLOUR: [u8; 4] = [0xdc, 0x26, 0x26, 0xff];
+/// The colour of the digits.
+const TEXT_COLOUR: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+/// A transparent pixel.
+const TRANSPARENT: [u8; 4] = [0; 4];
+
+/// Shows a tray icon with the number of unacknowledged notifications.
+///
+/// Must be used under the router, as opening a notification navigates to it.
+pub(super) fn use_tray() {
+    let navigator = use_navigator();
+    let tray = use_hook(|| init_tray_icon(Menu::new(), icon(0)));
+
+    use_effect(move || {
+        let notifications = NOTIFICATIONS.read();
+        update(&tray, notifications.as_deref().unwrap_or_default());
+    });
+
+    use_tray_menu_event_handler(move |event| match event.id.0.as_str() {
+        SHOW_ITEM => show_window(),
+        ACK_ALL_ITEM => {
+            spawn(ack_all());
+        }
+        QUIT_ITEM => quit(),
+        item => {
+            if let Some(id) = item
+                .strip_prefix(OPEN_ITEM_PREFIX)
+                .and_then(|id| id.parse().ok())
+            {
+                open_notification(navigator, id);
+            }
+        }
+    });
+}
+
+/// Updates the tray icon after the notifications have changed.
+fn update(tray: &TrayIcon, notifications: &[Notification]) {
+    let unacked = notifications.iter().filter(|ntf| !ntf.ack).count();
+
+    let tooltip = match unacked {
+        0 => "No unacknowledged notification".to_owned(),
+        1 => "1 unacknowledged notification".to_owned(),
+        _ => format!("{unacked} unacknowledged notifications"),
+    };
+
+    if let Err(error) = tray.set_icon(icon(unacked)) {
+        warn!(%error, "failed to update the tray icon");
+    }
+
+    if let Err(error) = tray.set_tooltip(Some(tooltip)) {
+        warn!(%error, "failed to update the tray tooltip");
+    }
+
+    // NOTE: The title is only shown next to the icon on macOS.
+    tray.set_title((unacked > 0).then(|| unacked.to_string()));
+
+    match menu(notifications, unacked) {
+        Ok(menu) => tray.set_menu(Some(Box::new(menu))),
+        Err(error) => warn!(%error, "failed to build the tray menu"),
+    }
+}
+
+/// Builds the tray menu.
+fn menu(
+    notifications: &[Notification],
+    unacked: usize,
+) -> Result<Menu, menu::Error> {
+    let menu = Menu::new();
+
+    menu.append(&MenuItem::with_id(SHOW_ITEM, "Show ntf-app", true, None))?;
+    menu.append(&PredefinedMenuItem::separator())?;
+
+    if notifications.is_empty() {
+        menu.append(&MenuItem::new("No notification", false, None))?;
+    }
+
+    for ntf in notifications.iter().rev().take(LATEST_COUNT) {
+        let id = format!("{OPEN_ITEM_PREFIX}{}", ntf.id);
+        menu.append(&MenuItem::with_id(id, label(ntf), true, None))?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator())?;
+    menu.append(&MenuItem::with_id(
+        ACK_ALL_ITEM,
+        "Ack all",
+        unacked > 0,
+        None,
+    ))?;
+    menu.append(&MenuItem::with_id(QUIT_ITEM, "Quit", true, None))?;
+
+    Ok(menu)
+}
+
+/// Returns the label of a notification in the menu.
+fn label(notification: &Notification) -> String {
+    let mark = if notification.ack { "✓" } else { "•" };
+    let message = notification.message.lines().next().unwrap_or_default();
+
+    let mut label = format!("{mark} #{} {message}", notification.id);
+    if let Some((index, _)) = label.char_indices().nth(LABEL_LENGTH) {
+        label.truncate(index);
+        label.push('…');
+    }
+
+    label
+}
+
+/// Acknowledges all the unacknowledged notifications.
+async fn ack_all() {
+    let ids = NOTIFICATIONS
+        .read()
+        .iter()
+        .flatten()
+        .filter(|ntf| !ntf.ack)
+        .map(|ntf| ntf.id)
+        .collect::<Vec<_>>();
+
+    for id in ids {
+        ack_notification(id).await;
+    }
+}
+
+/// Quits the application, as closing the window only hides it.
+fn quit() {
+    let window = dioxus::desktop::window();
+    window.set_close_behavior(WindowCloseBehaviour::WindowCloses);
+    window.close();
+}
+
+/// Draws the tray icon: a disc with the number of unacknowledged
+/// notifications, up to `9+`.
+fn icon(unacked: usize) -> Option<Icon> {
+    let text = match unacked {
+        0 => String::new(),
+        1..=9 => unacked.to_string(),
+        _ => "9+".to_owned(),
+    };
+    let glyphs = text.chars().filter_map(glyph).collect::<Vec<_>>();
+    let colour = if unacked == 0 {
+        IDLE_COLOUR
+    } else {
+        UNACKED_COLOUR
+    };
+
+    let mut rgba = Vec::with_capacity(ICON_SIZE * ICON_SIZE * 4);
+    for row in 0..ICON_SIZE {
+        for column in 0..ICON_SIZE {
+            let pixel = if is_text(&glyphs, column, row) {
+                TEXT_COLOUR
+            } else if is_disc(column, row) {
+                colour
+            } else {
+                TRANSPARENT
+            };
+
+            rgba.extend_from_slice(&pixel);
+        }
+    }
+
+    let size = u32::try_from(ICON_SIZE).ok()?;
+    Icon::from_rgba(rgba, size, size)
+        .inspect_err(|error| warn!(%error, "failed to draw the tray icon"))
+        .ok()
+}
+
+/// Returns whether a pixel is inside the disc of the icon.
+fn is_disc(column: usize, row: usize) -> bool {
+    // NOTE: Coordinates are doubled to measure from the centre of the pixels.
+    let dx = (2 * column + 1).abs_diff(ICON_SIZE);
+    let dy = (2 * row + 1).abs_diff(ICON_SIZE);
+    dx * dx + dy * dy <= ICON_SIZE * ICON_SIZE
+}
+
+/// Returns whether a pixel is part of the text centred on the icon.
+fn is_text(glyphs: &[[u8; GLYPH_HEIGHT]], column: usize, row: usize) -> bool {
+    let width = (glyphs.len() * (GLYPH_WIDTH + 1)).saturating_sub(1);
+    let left = ICON_SIZE.saturating_sub(width * GLYPH_SCALE) / 2;
+    let top = ICON_SIZE.saturating_sub(GLYPH_HEIGHT * GLYPH_SCALE) / 2;
+
+    let (Some(column), Some(row)) =
+        (column.checked_sub(left), row.checked_sub(top))
+    else {
+        return false;
+    };
+    let (column, row) = (column / GLYPH_SCALE, row / GLYPH_SCALE);
+    let offset = column % (GLYPH_WIDTH + 1);
+
+    offset < GLYPH_WIDTH
+        && glyphs
+            .get(column / (GLYPH_WIDTH + 1))
+            .and_then(|glyph| glyph.get(row))
+            .is_some_and(|bits| (bits >> (GLYPH_WIDTH - 1 - offset)) & 1 == 1)
+}
+
+/// Returns the bitmap of a character, one row of bits per line.
+const fn glyph(character: char) -> Option<[u8; GLYPH_HEIGHT]> {
+    let glyph = match character {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        _ => return None,
+    };
+
+    Some(glyph)
+}
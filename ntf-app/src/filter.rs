@@ -0,0 +1,335 @@
+//! The filters of the notification list, kept in the query of the URL.
+
+use std::{borrow::Cow, cmp::Reverse, fmt};
+
+use ntf_api::Notification;
+use percent_encoding::{
+    AsciiSet, NON_ALPHANUMERIC, percent_decode_str, utf8_percent_encode,
+};
+
+/// The characters escaped in the values of the query.
+const VALUE_ASCII_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// The characters escaped again in the escaped values of the query.
+const ESCAPE_ASCII_SET: &AsciiSet = &AsciiSet::EMPTY.add(b'%');
+
+/// The filters of the notification list.
+///
+/// In the URL, it looks like `?search=disk&tab=unread&tag=a&tag=b&sort=oldest`.
+/// Parameters with their default value are omitted, and the search and the
+/// tags are percent-encoded.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Filter {
+    /// The text to search in the message and tags.
+    pub search: String,
+    /// The notifications to show according to their acknowledgement.
+    pub tab: Tab,
+    /// The tags the notifications must all have.
+    pub tags: Vec<String>,
+    /// The order of the notifications.
+    pub sort: Sort,
+}
+
+/// The notifications to show according to their acknowledgement.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tab {
+    /// All notifications.
+    #[default]
+    All,
+    /// Only the unacknowledged notifications.
+    Unread,
+    /// Only the acknowledged notifications.
+    Acked,
+}
+
+/// The order of the notifications.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Sort {
+    /// The most recent first.
+    #[default]
+    Newest,
+    /// The oldest first.
+    Oldest,
+    /// The highest priority first, then the most recent.
+    Priority,
+}
+
+impl Filter {
+    /// Returns the matching notifications, in order.
+    pub fn apply<'a>(
+        &self,
+        notifications: &'a [Notification],
+    ) -> Vec<&'a Notification> {
+        let mut notifications = notifications
+            .iter()
+            .filter(|ntf| self.matches(ntf))
+            .collect::<Vec<_>>();
+
+        match self.sort {
+            Sort::Newest => notifications
+                .sort_by_key(|ntf| Reverse((ntf.created_at, ntf.id))),
+            Sort::Oldest => {
+                notifications.sort_by_key(|ntf| (ntf.created_at, ntf.id));
+            }
+            Sort::Priority => notifications.sort_by_key(|ntf| {
+                Reverse((ntf.priority, ntf.created_at, ntf.id))
+            }),
+        }
+
+        notifications
+    }
+
+    /// Returns whether a notification matches the filter.
+    fn matches(&self, notification: &Notification) -> bool {
+        let tab = match self.tab {
+            Tab::All => true,
+            Tab::Unread => !notification.ack,
+            Tab::Acked => notification.ack,
+        };
+
+        let tags = self.tags.iter().all(|tag| notification.tags.contains(tag));
+
+        let search = self.search.to_lowercase();
+        let search = search.is_empty()
+            || notification.message.to_lowercase().contains(&search)
+            || notification
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(&search));
+
+        tab && tags && search
+    }
+
+    /// Returns the filter with a tag added, or removed if already there.
+    #[must_use]
+    pub fn toggle_tag(&self, tag: &str) -> Self {
+        let mut filter = self.clone();
+
+        if filter.tags.iter().any(|existing| existing == tag) {
+            filter.tags.retain(|existing| existing != tag);
+        } else {
+            filter.tags.push(tag.to_owned());
+        }
+
+        filter
+    }
+}
+
+impl From<&str> for Filter {
+    fn from(query: &str) -> Self {
+        let mut filter = Self::default();
+
+        for (key, value) in
+            query.split('&').filter_map(|arg| arg.split_once('='))
+        {
+            let value = decode(value);
+
+            match key {
+                "search" => filter.search = value.into_owned(),
+                "tab" => {
+                    filter.tab = Tab::from_name(&value).unwrap_or_default();
+                }
+                "tag" if !value.is_empty() => {
+                    filter.tags.push(value.into_owned());
+                }
+                "sort" => {
+                    filter.sort = Sort::from_name(&value).unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+
+        filter
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut args = Vec::new();
+
+        if !self.search.is_empty() {
+            args.push(format!("search={}", encode(&self.search)));
+        }
+
+        if self.tab != Tab::default() {
+            args.push(format!("tab={}", self.tab.name()));
+        }
+
+        args.extend(self.tags.iter().map(|tag| format!("tag={}", encode(tag))));
+
+        if self.sort != Sort::default() {
+            args.push(format!("sort={}", self.sort.name()));
+        }
+
+        write!(f, "{}", args.join("&"))
+    }
+}
+
+/// Encodes a value of the query.
+///
+/// The router decodes the whole query before parsing it, so the value is
+/// escaped twice for `&`, `=` and `#` to still be escaped when splitting it.
+fn encode(value: &str) -> String {
+    let encoded = utf8_percent_encode(value, VALUE_ASCII_SET).to_string();
+    utf8_percent_encode(&encoded, ESCAPE_ASCII_SET).to_string()
+}
+
+/// Decodes a value of the query.
+fn decode(value: &str) -> Cow<'_, str> {
+    percent_decode_str(value).decode_utf8_lossy()
+}
+
+impl Tab {
+    /// All the tabs.
+    pub const ALL: [Self; 3] = [Self::All, Self::Unread, Self::Acked];
+
+    /// Returns the name of the tab.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Unread => "unread",
+            Self::Acked => "acked",
+        }
+    }
+
+    /// Returns the tab with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|tab| tab.name() == name)
+    }
+}
+
+impl Sort {
+    /// All the orders.
+    pub const ALL: [Self; 3] = [Self::Newest, Self::Oldest, Self::Priority];
+
+    /// Returns the name of the order.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Newest => "newest",
+            Self::Oldest => "oldest",
+            Self::Priority => "priority",
+        }
+    }
+
+    /// Returns the order with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|sort| sort.name() == name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use chrono::{DateTime, TimeDelta};
+    use ntf_api::Priority;
+
+    use crate::Route;
+
+    use super::*;
+
+    fn notification(id: usize, ack: bool, priority: Priority) -> Notification {
+        Notification {
+            ack,
+            tags: vec![format!("tag{}", id % 2)],
+            priority,
+            created_at: DateTime::UNIX_EPOCH
+                + TimeDelta::days(id.try_into().unwrap()),
+            ..Notification::new(id, format!("Message {id}"))
+        }
+    }
+
+    #[test]
+    fn filter_round_trips_through_the_query() {
+        let filter = Filter {
+            search: String::from("disk"),
+            tab: Tab::Unread,
+            tags: vec![String::from("a"), String::from("b")],
+            sort: Sort::Priority,
+        };
+
+        let query = filter.to_string();
+        assert_eq!(query, "search=disk&tab=unread&tag=a&tag=b&sort=priority");
+        assert_eq!(Filter::from(query.as_str()), filter);
+    }
+
+    #[test]
+    fn filter_round_trips_through_the_route_with_special_characters() {
+        let filter = Filter {
+            search: String::from("a&tag=b #1 100% é"),
+            tags: vec![String::from("x=y&z"), String::from("disk full")],
+            ..Filter::default()
+        };
+
+        let url = Route::List {
+            filter: filter.clone(),
+        }
+        .to_string();
+        let parsed = match url.parse::<Route>() {
+            Ok(Route::List { filter }) => Some(filter),
+            _ => None,
+        };
+        assert_eq!(parsed, Some(filter), "{url}");
+    }
+
+    #[test]
+    fn filter_decodes_the_values() {
+        let filter = Filter::from("search=disk%20full&tag=a%26b");
+        assert_eq!(filter.search, "disk full");
+        assert_eq!(filter.tags, ["a&b"]);
+    }
+
+    #[test]
+    fn default_filter_has_an_empty_query() {
+        assert_eq!(Filter::default().to_string(), "");
+        assert_eq!(Filter::from(""), Filter::default());
+    }
+
+    #[test]
+    fn unknown_values_fall_back_to_the_default() {
+        let filter = Filter::from("tab=nope&sort=random&other=1");
+        assert_eq!(filter, Filter::default());
+    }
+
+    #[test]
+    fn apply_filters_and_sorts() {
+        let notifications = [
+            notification(1, false, Priority::Low),
+            notification(2, true, Priority::Urgent),
+            notification(3, false, Priority::High),
+        ];
+
+        let filter = Filter {
+            tab: Tab::Unread,
+            sort: Sort::Priority,
+            ..Filter::default()
+        };
+        let ids = filter
+            .apply(&notifications)
+            .iter()
+            .map(|ntf| ntf.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [3, 1]);
+
+        let filter = Filter {
+            tags: vec![String::from("tag1")],
+            ..Filter::default()
+        };
+        let ids = filter
+            .apply(&notifications)
+            .iter()
+            .map(|ntf| ntf.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [3, 1]);
+
+        let filter = Filter {
+            search: String::from("message 2"),
+            ..Filter::default()
+        };
+        assert_eq!(filter.apply(&notifications).len(), 1);
+    }
+}
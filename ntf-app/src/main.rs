@@ -2,41 +2,87 @@
 
 #![expect(clippy::same_name_method, reason = "generated inside Dioxus macros")]
 
+mod cache;
+#[cfg(feature = "desktop")]
+mod desktop;
+mod filter;
+mod markdown;
+mod settings;
+mod shortcuts;
+mod state;
+mod toast;
+
+use std::{collections::BTreeSet, rc::Rc, time::Duration};
+
 use dioxus::prelude::*;
-use ntf_api::ApiClient;
+use ntf_api::{
+    Action, ActionKind, ActionOutcome, CreateError, CreateNotificationPayload,
+    Format, Notification, Priority, ValidationError,
+};
+
+use self::{
+    filter::{Filter, Sort, Tab},
+    settings::{SETTINGS, Theme, api_client, use_persisted_settings},
+    shortcuts::{Shortcut, ShortcutsHelp, keep_activation_keys},
+    state::{
+        LOAD_ERROR, NEW_NOTIFICATIONS, NOTIFICATIONS, QUEUE, STALE,
+        ack_notification, delete_notification, mark_seen,
+        refresh_notifications, use_live_notifications,
+    },
+    toast::Toasts,
+};
 
 /// Version info.
 const VERSION_WITH_GIT: &str = env!("VERSION_WITH_GIT");
 /// CSS for the app.
 const CSS: Asset = asset!("/assets/app.css");
-/// API endpoint.
-const ENDPOINT: &str = "http://localhost:3000";
 
 /// Pages of the application.
 #[derive(Debug, Clone, Routable)]
+#[rustfmt::skip]
 enum Route {
-    /// The list of notifications.
-    #[route("/")]
-    List,
-    /// The view for a given notification.
-    #[route("/:id")]
-    Show {
-        /// ID of the notification to show.
-        id: usize,
-    },
+    #[layout(Shell)]
+        /// The list of notifications.
+        #[route("/?:..filter")]
+        List {
+            /// The filters of the list.
+            filter: Filter,
+        },
+        /// The form to create a notification.
+        #[route("/new")]
+        New,
+        /// The settings of the application.
+        #[route("/settings")]
+        Settings,
+        /// The view for a given notification.
+        #[route("/:id")]
+        Show {
+            /// ID of the notification to show.
+            id: usize,
+        },
 }
 
 fn main() {
+    #[cfg(feature = "desktop")]
+    dioxus::LaunchBuilder::desktop()
+        .with_cfg(desktop::config())
+        .launch(App);
+
+    #[cfg(not(feature = "desktop"))]
     dioxus::launch(App);
 }
 
 #[component]
 fn App() -> Element {
+    use_persisted_settings();
+
     rsx! {
         document::Link { rel: "stylesheet", href: CSS }
 
-        Version {}
-        Router::<Route> {}
+        div { "data-theme": SETTINGS.read().theme.data_theme(),
+            Version {}
+            Router::<Route> {}
+        }
     }
 }
 
@@ -47,54 +93,193 @@ fn Version() -> Element {
     }
 }
 
+/// Focuses an element.
+async fn focus(element: Rc<MountedData>) {
+    if let Err(error) = element.set_focus(true).await {
+        warn!(%error, "failed to focus an element");
+    }
+}
+
+/// The layout shared by all pages.
 #[component]
-fn List() -> Element {
-    let fetch_notifications = async move || {
-        ApiClient::new(ENDPOINT)
-            .list_notifications()
-            .await
-            .unwrap_or_default()
-    };
+fn Shell() -> Element {
+    use_live_notifications();
 
-    let mut notifications = use_resource(fetch_notifications);
+    #[cfg(feature = "desktop")]
+    desktop::use_desktop_integration();
 
-    let mut update_notifications = async move || {
-        notifications.set(Some(fetch_notifications().await));
-    };
+    rsx! {
+        Outlet::<Route> {}
+        Toasts {}
+    }
+}
 
-    let reload_notifications = move |_| async move {
-        update_notifications().await;
-    };
+#[component]
+fn List(
+    /// The filters of the list.
+    filter: Filter,
+) -> Element {
+    let mut selected = use_signal(|| 0_usize);
+    let mut help = use_signal(|| false);
+    let search_input = use_signal(|| None);
 
-    let ack_notification = move |id| async move {
-        let _ignored = ApiClient::new(ENDPOINT).ack_notification(id).await;
-        update_notifications().await;
+    let unread = NOTIFICATIONS
+        .read()
+        .iter()
+        .flatten()
+        .filter(|ntf| !ntf.ack)
+        .count();
+
+    let ids = NOTIFICATIONS
+        .read()
+        .as_deref()
+        .map_or_else(Vec::new, |ntfs| {
+            filter.apply(ntfs).iter().map(|ntf| ntf.id).collect()
+        });
+    let index = selected().min(ids.len().saturating_sub(1));
+    let selected_id = ids.get(index).copied();
+
+    let onkeydown = move |event: KeyboardEvent| {
+        let Some(shortcut) = Shortcut::from_event(&event) else {
+            return;
+        };
+        event.prevent_default();
+
+        match shortcut {
+            Shortcut::Next => {
+                selected.set((index + 1).min(ids.len().saturating_sub(1)));
+            }
+            Shortcut::Previous => selected.set(index.saturating_sub(1)),
+            Shortcut::Open => {
+                if let Some(id) = selected_id {
+                    navigator().push(Route::Show { id });
+                }
+            }
+            Shortcut::Ack => {
+                if let Some(id) = selected_id {
+                    spawn(ack_notification(id));
+                }
+            }
+            Shortcut::Delete => {
+                if let Some(id) = selected_id {
+                    spawn(delete_notification(id));
+                }
+            }
+            Shortcut::Search => {
+                if let Some(input) = search_input() {
+                    spawn(focus(input));
+                }
+            }
+            Shortcut::Refresh => {
+                spawn(refresh_notifications());
+            }
+            Shortcut::Back => help.set(false),
+            Shortcut::Help => help.toggle(),
+        }
     };
 
-    let delete_notification = move |id| async move {
-        let _ignored = ApiClient::new(ENDPOINT).delete_notification(id).await;
-        update_notifications().await;
+    rsx! {
+        div {
+            class: "outline-none",
+            tabindex: "0",
+            onmounted: move |event: MountedEvent| focus(event.data()),
+            onkeydown,
+            ListContent {
+                filter,
+                selected_id,
+                search_input,
+                unread,
+            }
+            ShortcutsHelp { open: help }
+        }
+    }
+}
+
+/// The content of the list of notifications.
+#[component]
+fn ListContent(
+    /// The filters of the list.
+    filter: Filter,
+    /// The ID of the notification selected with the keyboard, if any.
+    selected_id: Option<usize>,
+    /// The search field, once mounted.
+    search_input: Signal<Option<Rc<MountedData>>>,
+    /// The number of unacknowledged notifications.
+    unread: usize,
+) -> Element {
+    let reload_notifications = move |_| async move {
+        refresh_notifications().await;
     };
 
     rsx! {
-        div { class: "navbar bg-base-100 shadow-sm" }
+        div { class: "navbar bg-base-100 shadow-sm gap-4",
+            Link { to: Route::New, class: "btn btn-primary", "+" }
+            Link { to: Route::Settings, class: "btn btn-ghost", "⚙" }
+            if unread > 0 {
+                span { class: "badge badge-primary", "{unread} unread" }
+            }
+            if STALE() {
+                span { class: "badge badge-warning", "Stale: the server cannot be reached" }
+            }
+            if !QUEUE.read().is_empty() {
+                span { class: "badge badge-ghost", "{QUEUE.read().len()} pending" }
+            }
+        }
+
+        Filters { filter: filter.clone(), search_input }
+
+        if let Some(error) = LOAD_ERROR.read().as_deref() {
+            div { role: "alert", class: "alert alert-error alert-soft m-2",
+                span { "Could not load the notifications: {error}." }
+                button {
+                    class: "btn btn-sm",
+                    onkeydown: keep_activation_keys,
+                    onclick: reload_notifications,
+                    "Retry"
+                }
+            }
+        }
 
         ul { class: "list bg-base-100 rounded-box shadow-md",
-            if let Some(ntfs) = notifications.read().as_deref() {
-                for ntf in ntfs {
-                    li { class: "list-row",
-                        Link {
-                            to: Route::Show { id: ntf.id },
-                            class: "list-col-grow flex gap-4",
-                            h2 { class: "text-4xl", "#{ntf.id}" }
-                            p { "{ntf.message}" }
+            if NOTIFICATIONS.read().is_none() && LOAD_ERROR.read().is_none() {
+                for index in 0..3 {
+                    li { key: "{index}", class: "list-row",
+                        div { class: "skeleton h-10 w-16" }
+                        div { class: "skeleton h-10 list-col-grow" }
+                    }
+                }
+            }
+
+            if let Some(ntfs) = NOTIFICATIONS.read().as_deref() {
+                for ntf in filter.apply(ntfs) {
+                    li {
+                        class: "list-row",
+                        class: if selected_id == Some(ntf.id) { "bg-base-200" },
+                        class: if !ntf.ack && NEW_NOTIFICATIONS.read().contains(&ntf.id) { "bg-primary/10" },
+                        div {
+                            class: "list-col-grow",
+                            onkeydown: keep_activation_keys,
+                            Link {
+                                to: Route::Show { id: ntf.id },
+                                class: "flex gap-4",
+                                h2 { class: "text-4xl", "#{ntf.id}" }
+                                p { "{ntf.message}" }
+                                if !ntf.ack && NEW_NOTIFICATIONS.read().contains(&ntf.id) {
+                                    span { class: "badge badge-sm badge-primary", "New" }
+                                }
+                            }
                         }
 
                         if ntf.ack {
-                            button { class: "btn btn-active btn-success", "✓" }
+                            button {
+                                class: "btn btn-active btn-success",
+                                onkeydown: keep_activation_keys,
+                                "✓"
+                            }
                         } else {
                             button {
                                 class: "btn btn-soft btn-success",
+                                onkeydown: keep_activation_keys,
                                 onclick: {
                                     let id = ntf.id;
                                     move |_| ack_notification(id)
@@ -104,6 +289,7 @@ fn List() -> Element {
                         }
                         button {
                             class: "btn btn-soft btn-error",
+                            onkeydown: keep_activation_keys,
                             onclick: {
                                 let id = ntf.id;
                                 move |_| delete_notification(id)
@@ -116,6 +302,7 @@ fn List() -> Element {
                 li { class: "p-4 pb-2 text-xs tracking-wide",
                     button {
                         class: "btn btn-primary",
+                        onkeydown: keep_activation_keys,
                         onclick: reload_notifications,
                         "Reload notifications"
                     }
@@ -125,72 +312,767 @@ fn List() -> Element {
     }
 }
 
+#[component]
+fn New() -> Element {
+    let mut message = use_signal(String::new);
+    let mut tags = use_signal(String::new);
+    let mut priority = use_signal(Priority::default);
+    let mut format = use_signal(Format::default);
+    let actions = use_signal(Vec::<Action>::new);
+    let mut message_error = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+    let mut submitting = use_signal(|| false);
+
+    let submit = move |event: FormEvent| async move {
+        event.prevent_default();
+
+        let payload = CreateNotificationPayload {
+            message: message.peek().trim().to_owned(),
+            format: *format.peek(),
+            tags: parse_tags(&tags.peek()),
+            priority: *priority.peek(),
+            actions: actions.peek().clone(),
+        };
+
+        if payload.message.is_empty() {
+            message_error
+                .set(Some(String::from("The message cannot be empty.")));
+            return;
+        }
+
+        message_error.set(None);
+        error.set(None);
+        submitting.set(true);
+
+        let result = api_client().create_notification(&payload).await;
+        submitting.set(false);
+
+        match result {
+            Ok(notification) => {
+                navigator().push(Route::Show {
+                    id: notification.id,
+                });
+            }
+            Err(CreateError::ValidationErrors(validation_errors)) => {
+                let (message_errors, other_errors): (Vec<_>, Vec<_>) =
+                    validation_errors.into_iter().partition(|field_error| {
+                        field_error.field == "message"
+                    });
+
+                if let Some(field_error) = message_errors.first() {
+                    message_error
+                        .set(Some(format!("{}.", field_error.message)));
+                }
+                if !other_errors.is_empty() {
+                    error.set(Some(format!(
+                        "The server has rejected the notification: {}.",
+                        ValidationError::join(&other_errors)
+                    )));
+                }
+            }
+            Err(CreateError::PayloadError(payload_error)) => {
+                error.set(Some(format!(
+                    "The server has rejected the notification: {payload_error}."
+                )));
+            }
+            Err(CreateError::ApiError(api_error)) => {
+                error.set(Some(format!(
+                    "Could not create the notification: {api_error}."
+                )));
+            }
+        }
+    };
+
+    rsx! {
+        div { class: "navbar bg-base-100 shadow-sm",
+            Link {
+                to: Route::List {
+                    filter: Filter::default(),
+                },
+                class: "btn btn-primary",
+                "<"
+            }
+        }
+
+        form { class: "flex flex-col gap-4 max-w-md p-4", onsubmit: submit,
+            fieldset { class: "fieldset",
+                legend { class: "fieldset-legend", "New notification" }
+
+                label { class: "label", "Message" }
+                textarea {
+                    class: "textarea w-full",
+                    class: if message_error.read().is_some() { "textarea-error" },
+                    value: "{message}",
+                    oninput: move |event| message.set(event.value()),
+                }
+                if let Some(message_error) = message_error() {
+                    p { class: "label text-error", "{message_error}" }
+                }
+
+                label { class: "label", "Tags (comma-separated)" }
+                input {
+                    class: "input w-full",
+                    value: "{tags}",
+                    oninput: move |event| tags.set(event.value()),
+                }
+
+                label { class: "label", "Priority" }
+                select {
+                    class: "select w-full",
+                    onchange: move |event| {
+                        if let Ok(value) = event.value().parse() {
+                            priority.set(value);
+                        }
+                    },
+                    for value in Priority::ALL {
+                        option {
+                            value: value.name(),
+                            selected: priority() == value,
+                            "{value}"
+                        }
+                    }
+                }
+
+                label { class: "label", "Format" }
+                select {
+                    class: "select w-full",
+                    onchange: move |event| {
+                        if let Ok(value) = event.value().parse() {
+                            format.set(value);
+                        }
+                    },
+                    for value in Format::ALL {
+                        option {
+                            value: value.name(),
+                            selected: format() == value,
+                            "{value}"
+                        }
+                    }
+                }
+
+                ActionsEditor { actions }
+            }
+
+            if let Some(error) = error() {
+                div { role: "alert", class: "alert alert-error alert-soft", "{error}" }
+            }
+
+            button {
+                r#type: "submit",
+                class: "btn btn-primary",
+                disabled: submitting(),
+                "Create"
+            }
+        }
+    }
+}
+
+/// The editor of the actions of a new notification.
+#[component]
+fn ActionsEditor(
+    /// The actions being edited.
+    actions: Signal<Vec<Action>>,
+) -> Element {
+    let current = actions.read().clone();
+
+    rsx! {
+        label { class: "label", "Actions" }
+        for (index, action) in current.into_iter().enumerate() {
+            div {
+                key: "{index}",
+                class: "flex flex-col gap-2 rounded-box border border-base-300 p-2",
+                div { class: "flex gap-2",
+                    input {
+                        class: "input",
+                        placeholder: "Name",
+                        value: "{action.name}",
+                        oninput: move |event: FormEvent| {
+                            edit_action(actions, index, |action| action.name = event.value());
+                        },
+                    }
+                    input {
+                        class: "input",
+                        placeholder: "Label",
+                        value: "{action.label}",
+                        oninput: move |event: FormEvent| {
+                            edit_action(actions, index, |action| action.label = event.value());
+                        },
+                    }
+                }
+                div { class: "flex gap-2",
+                    select {
+                        class: "select w-auto",
+                        onchange: move |event: FormEvent| {
+                            edit_action(
+                                actions,
+                                index,
+                                |action| set_action_type(&mut action.kind, &event.value()),
+                            );
+                        },
+                        option {
+                            value: "url",
+                            selected: matches!(action.kind, ActionKind::Url { .. }),
+                            "url"
+                        }
+                        option {
+                            value: "webhook",
+                            selected: matches!(action.kind, ActionKind::Webhook { .. }),
+                            "webhook"
+                        }
+                    }
+                    input {
+                        class: "input grow",
+                        placeholder: "URL",
+                        value: "{action_url(&action.kind)}",
+                        oninput: move |event: FormEvent| {
+                            edit_action(
+                                actions,
+                                index,
+                                |action| set_action_url(&mut action.kind, event.value()),
+                            );
+                        },
+                    }
+                    button {
+                        r#type: "button",
+                        class: "btn btn-soft btn-error",
+                        onclick: move |_| {
+                            let mut actions = actions.write();
+                            if index < actions.len() {
+                                actions.remove(index);
+                            }
+                        },
+                        "✗"
+                    }
+                }
+                if let ActionKind::Webhook { body, .. } = &action.kind {
+                    textarea {
+                        class: "textarea w-full",
+                        placeholder: "Body (optional)",
+                        value: body.clone().unwrap_or_default(),
+                        oninput: move |event: FormEvent| {
+                            edit_action(
+                                actions,
+                                index,
+                                |action| set_action_body(&mut action.kind, event.value()),
+                            );
+                        },
+                    }
+                }
+            }
+        }
+        button {
+            r#type: "button",
+            class: "btn btn-sm w-fit",
+            onclick: move |_| {
+                actions
+                    .write()
+                    .push(Action {
+                        name: String::new(),
+                        label: String::new(),
+                        kind: ActionKind::Url { url: String::new() },
+                        outcome: None,
+                    });
+            },
+            "Add an action"
+        }
+    }
+}
+
+/// Edits an action of the list, if it is still there.
+fn edit_action(
+    mut actions: Signal<Vec<Action>>,
+    index: usize,
+    change: impl FnOnce(&mut Action),
+) {
+    if let Some(action) = actions.write().get_mut(index) {
+        change(action);
+    }
+}
+
+/// Returns the URL of an action.
+fn action_url(kind: &ActionKind) -> &str {
+    match kind {
+        ActionKind::Url { url } | ActionKind::Webhook { url, .. } => url,
+    }
+}
+
+/// Sets the type of an action, `url` or `webhook`, keeping its URL.
+fn set_action_type(kind: &mut ActionKind, name: &str) {
+    let url = action_url(kind).to_owned();
+
+    *kind = match name {
+        "webhook" => ActionKind::Webhook { url, body: None },
+        _ => ActionKind::Url { url },
+    };
+}
+
+/// Sets the URL of an action.
+fn set_action_url(kind: &mut ActionKind, value: String) {
+    match kind {
+        ActionKind::Url { url } | ActionKind::Webhook { url, .. } => {
+            *url = value;
+        }
+    }
+}
+
+/// Sets the body of a webhook action, none when empty.
+fn set_action_body(kind: &mut ActionKind, value: String) {
+    if let ActionKind::Webhook { body, .. } = kind {
+        *body = Some(value).filter(|value| !value.is_empty());
+    }
+}
+
+/// Parses comma-separated tags, ignoring blank and duplicate ones.
+fn parse_tags(tags: &str) -> Vec<String> {
+    let mut parsed = Vec::<String>::new();
+
+    for tag in tags.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !parsed.iter().any(|existing| existing == tag) {
+            parsed.push(tag.to_owned());
+        }
+    }
+
+    parsed
+}
+
+/// The controls to search, filter and sort the list.
+#[component]
+fn Filters(
+    /// The current filters.
+    filter: Filter,
+    /// The search field, once mounted.
+    mut search_input: Signal<Option<Rc<MountedData>>>,
+) -> Element {
+    let tags = NOTIFICATIONS
+        .read()
+        .iter()
+        .flatten()
+        .flat_map(|ntf| ntf.tags.iter().cloned())
+        .chain(filter.tags.iter().cloned())
+        .collect::<BTreeSet<_>>();
+
+    let apply = |filter: Filter| {
+        navigator().replace(Route::List { filter });
+    };
+
+    rsx! {
+        div { class: "flex flex-wrap items-center gap-2 p-2",
+            input {
+                r#type: "search",
+                class: "input",
+                placeholder: "Search",
+                value: "{filter.search}",
+                onmounted: move |event: MountedEvent| {
+                    search_input.set(Some(event.data()));
+                },
+                onkeydown: |event: KeyboardEvent| event.stop_propagation(),
+                oninput: {
+                    let filter = filter.clone();
+                    move |event: FormEvent| {
+                        apply(Filter {
+                            search: event.value(),
+                            ..filter.clone()
+                        });
+                    }
+                },
+            }
+
+            div { role: "tablist", class: "tabs tabs-box",
+                for tab in Tab::ALL {
+                    a {
+                        role: "tab",
+                        class: "tab",
+                        class: if filter.tab == tab { "tab-active" },
+                        onkeydown: keep_activation_keys,
+                        onclick: {
+                            let filter = filter.clone();
+                            move |_| apply(Filter { tab, ..filter.clone() })
+                        },
+                        "{tab.name()}"
+                    }
+                }
+            }
+
+            select {
+                class: "select w-auto",
+                onkeydown: |event: KeyboardEvent| event.stop_propagation(),
+                onchange: {
+                    let filter = filter.clone();
+                    move |event: FormEvent| {
+                        if let Some(sort) = Sort::from_name(&event.value()) {
+                            apply(Filter { sort, ..filter.clone() });
+                        }
+                    }
+                },
+                for sort in Sort::ALL {
+                    option {
+                        value: sort.name(),
+                        selected: filter.sort == sort,
+                        "{sort.name()}"
+                    }
+                }
+            }
+
+            for tag in tags {
+                button {
+                    class: "badge cursor-pointer",
+                    class: if filter.tags.contains(&tag) { "badge-primary" } else { "badge-outline" },
+                    onkeydown: keep_activation_keys,
+                    onclick: {
+                        let filter = filter.toggle_tag(&tag);
+                        move |_| apply(filter.clone())
+                    },
+                    "{tag}"
+                }
+            }
+        }
+    }
+}
+
 #[component]
 fn Show(
     /// ID of the notification to show.
     id: usize,
 ) -> Element {
-    let fetch_notification = move || async move {
-        ApiClient::new(ENDPOINT).get_notification(id).await
-    };
+    let fetch_notification =
+        move || async move { api_client().get_notification(id).await };
 
     let mut notification = use_resource(fetch_notification);
 
+    use_effect(move || mark_seen(id));
+
     let ack_notification = move |id| async move {
-        let _ignored = ApiClient::new(ENDPOINT).ack_notification(id).await;
-        notification.set(Some(fetch_notification().await));
+        if let Some(Ok(ntf)) = notification.write().as_mut() {
+            ntf.ack = true;
+        }
+
+        state::ack_notification(id).await;
+
+        // NOTE: When offline, the acknowledgement is queued and the
+        // notification kept as is.
+        if let Ok(ntf) = fetch_notification().await {
+            notification.set(Some(Ok(ntf)));
+        }
     };
 
     let delete_notification = move |id| async move {
-        let _ignored = ApiClient::new(ENDPOINT).delete_notification(id).await;
-        navigator().replace(Route::List);
+        state::delete_notification(id).await;
+        navigator().replace(Route::List {
+            filter: Filter::default(),
+        });
+    };
+
+    let mut help = use_signal(|| false);
+
+    let onkeydown = move |event: KeyboardEvent| {
+        let Some(shortcut) = Shortcut::from_event(&event) else {
+            return;
+        };
+
+        match shortcut {
+            Shortcut::Ack => {
+                spawn(ack_notification(id));
+            }
+            Shortcut::Delete => {
+                spawn(delete_notification(id));
+            }
+            Shortcut::Back if help() => help.set(false),
+            Shortcut::Back => {
+                navigator().push(Route::List {
+                    filter: Filter::default(),
+                });
+            }
+            Shortcut::Help => help.toggle(),
+            Shortcut::Next
+            | Shortcut::Previous
+            | Shortcut::Open
+            | Shortcut::Search
+            | Shortcut::Refresh => return,
+        }
+        event.prevent_default();
     };
 
     rsx! {
-        div { class: "navbar bg-base-100 shadow-sm",
-            Link { to: Route::List, class: "btn btn-primary", "<" }
-        }
-
-        if let Some(result) = notification.read().as_ref() {
-            div { class: "hero bg-base-100",
-                div { class: "hero-content text-center",
-                    div { class: "max-w-md",
-                        {
-                            match result {
-                                Ok(ntf) => rsx! {
-                                    h1 { class: "text-5xl font-bold", "#{ntf.id}" }
-                                    p { class: "py-6", "{ntf.message}" }
-                                    div { class: "flex flex-col gap-2 min-w-3xs",
-                                        if ntf.ack {
-                                            button { class: "btn btn-active btn-success", "✓" }
-                                        } else {
+        div {
+            class: "outline-none",
+            tabindex: "0",
+            onmounted: move |event: MountedEvent| focus(event.data()),
+            onkeydown,
+            div { class: "navbar bg-base-100 shadow-sm",
+                Link {
+                    to: Route::List {
+                        filter: Filter::default(),
+                    },
+                    class: "btn btn-primary",
+                    "<"
+                }
+            }
+
+            if let Some(result) = notification.read().as_ref() {
+                div { class: "hero bg-base-100",
+                    div { class: "hero-content text-center",
+                        div { class: "max-w-md",
+                            {
+                                match result {
+                                    Ok(ntf) => rsx! {
+                                        h1 { class: "text-5xl font-bold", "#{ntf.id}" }
+                                        {
+                                            match ntf.format {
+                                                Format::Plain => rsx! {
+                                                    p { class: "py-6 whitespace-pre-wrap", "{ntf.message}" }
+                                                },
+                                                Format::Markdown => rsx! {
+                                                    div {
+                                                        class: "markdown py-6 text-left",
+                                                        dangerous_inner_html: markdown::to_html(&ntf.message),
+                                                    }
+                                                },
+                                            }
+                                        }
+                                        Actions {
+                                            id: ntf.id,
+                                            actions: ntf.actions.clone(),
+                                            onrun: move |ntf| notification.set(Some(Ok(ntf))),
+                                        }
+                                        div { class: "flex flex-col gap-2 min-w-3xs",
+                                            if ntf.ack {
+                                                button {
+                                                    class: "btn btn-active btn-success",
+                                                    onkeydown: keep_activation_keys,
+                                                    "✓"
+                                                }
+                                            } else {
+                                                button {
+                                                    class: "btn btn-soft btn-success",
+                                                    onkeydown: keep_activation_keys,
+                                                    onclick: {
+                                                        let id = ntf.id;
+                                                        move |_| ack_notification(id)
+                                                    },
+                                                    "✓"
+                                                }
+                                            }
                                             button {
-                                                class: "btn btn-soft btn-success",
+                                                class: "btn btn-soft btn-error",
+                                                onkeydown: keep_activation_keys,
                                                 onclick: {
                                                     let id = ntf.id;
-                                                    move |_| ack_notification(id)
+                                                    move |_| delete_notification(id)
                                                 },
-                                                "✓"
+                                                "✗"
                                             }
                                         }
-                                        button {
-                                            class: "btn btn-soft btn-error",
-                                            onclick: {
-                                                let id = ntf.id;
-                                                move |_| delete_notification(id)
-                                            },
-                                            "✗"
-                                        }
-                                    }
-                                },
-                                Err(error) => rsx! {
-                                    div { role: "alert", class: "alert alert-error alert-soft", "Error: {error.to_string()}" }
+                                    },
+                                    Err(error) => rsx! {
+                                        div { role: "alert", class: "alert alert-error alert-soft", "Error: {error.to_string()}" }
+                                    },
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            ShortcutsHelp { open: help }
+        }
+    }
+}
+
+/// The buttons to run the actions of a notification.
+#[component]
+fn Actions(
+    /// The ID of the notification.
+    id: usize,
+    /// The actions of the notification.
+    actions: Vec<Action>,
+    /// Called with the notification updated after running an action.
+    onrun: EventHandler<Notification>,
+) -> Element {
+    let mut running = use_signal(|| None::<String>);
+
+    let run = move |name: String| async move {
+        running.set(Some(name.clone()));
+        let notification = state::run_action(id, &name).await;
+        running.set(None);
+
+        if let Some(notification) = notification {
+            onrun.call(notification);
+        }
+    };
+
+    rsx! {
+        if !actions.is_empty() {
+            div { class: "flex flex-wrap justify-center gap-2 pb-6",
+                for action in actions {
+                    match action.kind {
+                        ActionKind::Url { url } => rsx! {
+                            a {
+                                key: "{action.name}",
+                                class: "btn btn-outline",
+                                href: "{url}",
+                                target: "_blank",
+                                rel: "noopener noreferrer",
+                                onkeydown: keep_activation_keys,
+                                "{action.label}"
+                            }
+                        },
+                        ActionKind::Webhook { .. } => rsx! {
+                            button {
+                                key: "{action.name}",
+                                class: "btn btn-outline",
+                                disabled: running.read().is_some(),
+                                onkeydown: keep_activation_keys,
+                                onclick: {
+                                    let name = action.name.clone();
+                                    move |_| run(name.clone())
                                 },
+                                if running.read().as_ref() == Some(&action.name) {
+                                    span { class: "loading loading-spinner loading-xs" }
+                                }
+                                "{action.label}"
+                                if let Some(outcome) = &action.outcome {
+                                    ActionOutcomeBadge { outcome: outcome.clone() }
+                                }
                             }
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A badge showing the outcome of the last run of an action.
+#[component]
+fn ActionOutcomeBadge(
+    /// The outcome of the action.
+    outcome: ActionOutcome,
+) -> Element {
+    let title = format!("Run at {}", outcome.run_at);
+    let text = match (&outcome.error, outcome.status) {
+        (Some(error), _) => format!("✗ {error}"),
+        (None, Some(status)) if outcome.is_success() => format!("✓ {status}"),
+        (None, Some(status)) => format!("✗ {status}"),
+        (None, None) => String::from("✓"),
+    };
+
+    rsx! {
+        span {
+            class: "badge badge-sm",
+            class: if outcome.is_success() { "badge-success" } else { "badge-error" },
+            title,
+            "{text}"
+        }
+    }
+}
+
+#[component]
+fn Settings() -> Element {
+    let mut draft = use_signal(|| SETTINGS.peek().clone());
+    let mut connection = use_signal(|| None::<Result<(), String>>);
+
+    let test_connection = move |_| async move {
+        connection.set(None);
+        let api = draft.peek().api_client();
+        let result = api.status().await.map_err(|error| error.to_string());
+        connection.set(Some(result));
+    };
+
+    let save = move |_| {
+        *SETTINGS.write() = draft.peek().clone();
+        navigator().push(Route::List {
+            filter: Filter::default(),
+        });
+    };
+
+    rsx! {
+        div { class: "navbar bg-base-100 shadow-sm",
+            Link {
+                to: Route::List {
+                    filter: Filter::default(),
+                },
+                class: "btn btn-primary",
+                "<"
+            }
+        }
+
+        div { class: "flex flex-col gap-4 max-w-md p-4",
+            fieldset { class: "fieldset",
+                legend { class: "fieldset-legend", "Server" }
+                label { class: "label", "URL" }
+                input {
+                    r#type: "url",
+                    class: "input w-full",
+                    value: "{draft.read().server_url}",
+                    oninput: move |event| draft.write().server_url = event.value(),
+                }
+                label { class: "label", "Token" }
+                input {
+                    r#type: "password",
+                    class: "input w-full",
+                    value: draft.read().token.clone().unwrap_or_default(),
+                    oninput: move |event| {
+                        let token = event.value();
+                        draft.write().token = (!token.is_empty()).then_some(token);
+                    },
+                }
+                div { class: "flex items-center gap-2 mt-2",
+                    button { class: "btn btn-sm", onclick: test_connection, "Test connection" }
+                    match connection.read().as_ref() {
+                        Some(Ok(())) => rsx! {
+                            span { class: "text-success", "Connected." }
+                        },
+                        Some(Err(error)) => rsx! {
+                            span { class: "text-error", "{error}" }
+                        },
+                        None => rsx! {},
+                    }
+                }
+            }
+
+            fieldset { class: "fieldset",
+                legend { class: "fieldset-legend", "Preferences" }
+                label { class: "label", "Theme" }
+                select {
+                    class: "select w-full",
+                    onchange: move |event| {
+                        if let Some(theme) = Theme::from_name(&event.value()) {
+                            draft.write().theme = theme;
+                        }
+                    },
+                    for theme in Theme::ALL {
+                        option {
+                            value: theme.name(),
+                            selected: draft.read().theme == theme,
+                            "{theme.name()}"
+                        }
+                    }
+                }
+                label { class: "label", "Refresh interval (seconds)" }
+                input {
+                    r#type: "number",
+                    class: "input w-full",
+                    min: 1,
+                    value: "{draft.read().refresh_interval.as_secs()}",
+                    oninput: move |event| {
+                        if let Ok(seconds @ 1..) = event.value().parse() {
+                            draft.write().refresh_interval = Duration::from_secs(seconds);
+                        }
+                    },
+                }
+                if cfg!(feature = "desktop") {
+                    label { class: "label mt-2",
+                        input {
+                            r#type: "checkbox",
+                            class: "toggle",
+                            checked: draft.read().mute_low_priority,
+                            onchange: move |event| draft.write().mute_low_priority = event.checked(),
                         }
+                        "Mute low-priority notifications"
                     }
                 }
             }
+
+            button { class: "btn btn-primary", onclick: save, "Save" }
         }
     }
 }
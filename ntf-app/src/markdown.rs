@@ -0,0 +1,59 @@
+//! The rendering of Markdown messages.
+
+use pulldown_cmark::{Options, Parser, html};
+
+/// Renders a Markdown message to sanitised HTML.
+///
+/// Links get `target="_blank"`, so that they open in a new tab on the web. On
+/// desktop and mobile, the webview opens them in the default browser.
+pub fn to_html(message: &str) -> String {
+    let parser = Parser::new_ext(
+        message,
+        Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES,
+    );
+
+    let mut unsafe_html = String::new();
+    html::push_html(&mut unsafe_html, parser);
+
+    ammonia::Builder::default()
+        .link_rel(Some("noopener noreferrer"))
+        .set_tag_attribute_value("a", "target", "_blank")
+        .clean(&unsafe_html)
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn markdown_is_rendered() {
+        assert_eq!(
+            to_html("Disk **full**: `df -h`"),
+            "<p>Disk <strong>full</strong>: <code>df -h</code></p>\n"
+        );
+    }
+
+    #[test]
+    fn links_open_externally() {
+        assert_eq!(
+            to_html("[docs](https://example.com)"),
+            "<p><a href=\"https://example.com\" target=\"_blank\" \
+                rel=\"noopener noreferrer\">docs</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn html_is_sanitised() {
+        let html = to_html(
+            "<script>alert(1)</script>\n\n[x](javascript:alert(1))\n\n\
+                <img src=x onerror=alert(1)>",
+        );
+
+        assert!(!html.contains("<script"), "{html}");
+        assert!(!html.contains("href=\"javascript"), "{html}");
+        assert!(!html.contains("onerror"), "{html}");
+    }
+}
@@ -0,0 +1,303 @@
+//! The settings of the application.
+//!
+//! The settings are persisted in `$XDG_CONFIG_HOME/ntf/app.toml` on native
+//! platforms, and in the local storage on the web.
+
+use std::time::Duration;
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use ntf_api::ApiClient;
+
+/// The default URL of the server.
+const DEFAULT_SERVER_URL: &str = "http://localhost:3000";
+/// The default interval between two refreshes of the notifications.
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The settings of the application.
+pub static SETTINGS: GlobalSignal<Settings> = Signal::global(Settings::load);
+
+/// The settings of the application.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The base URL of the server.
+    pub server_url: String,
+    /// The token to authenticate with, if any.
+    pub token: Option<String>,
+    /// The colour theme.
+    pub theme: Theme,
+    /// The interval between two refreshes of the notifications, when the
+    /// server cannot push them.
+    #[serde(with = "seconds")]
+    pub refresh_interval: Duration,
+    /// Do not show desktop notifications for low-priority items.
+    pub mute_low_priority: bool,
+}
+
+/// A colour theme.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    /// Follow the preference of the system.
+    System,
+    /// A light theme.
+    Light,
+    /// A dark theme.
+    #[default]
+    Dark,
+}
+
+/// Returns an API client for the configured server.
+pub fn api_client() -> ApiClient {
+    SETTINGS.peek().api_client()
+}
+
+/// Saves the settings each time they change.
+pub fn use_persisted_settings() {
+    let mut saved = use_signal(|| SETTINGS.peek().clone());
+
+    use_effect(move || {
+        let settings = SETTINGS.read();
+
+        if *settings != *saved.peek() {
+            settings.save();
+            saved.set(settings.clone());
+        }
+    });
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            server_url: DEFAULT_SERVER_URL.to_owned(),
+            token: None,
+            theme: Theme::default(),
+            refresh_interval: DEFAULT_REFRESH_INTERVAL,
+            mute_low_priority: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Returns an API client for the server.
+    pub fn api_client(&self) -> ApiClient {
+        let client = ApiClient::new(&self.server_url);
+
+        match &self.token {
+            Some(token) => client.with_token(token),
+            None => client,
+        }
+    }
+
+    /// Loads the settings, falling back to the defaults if there are none.
+    fn load() -> Self {
+        storage::read()
+            .and_then(|contents| {
+                toml::from_str(&contents)
+                    .inspect_err(|error| warn!(%error, "invalid settings"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Saves the settings.
+    fn save(&self) {
+        match toml::to_string(self) {
+            Ok(contents) => storage::write(&contents),
+            Err(error) => warn!(%error, "failed to serialise the settings"),
+        }
+    }
+}
+
+impl Theme {
+    /// All the themes.
+    pub const ALL: [Self; 3] = [Self::System, Self::Light, Self::Dark];
+
+    /// Returns the name of the theme.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Light => "light",
+            Self::Dark => "dark",
+        }
+    }
+
+    /// Returns the daisyUI theme to use, if not the one of the system.
+    pub const fn data_theme(self) -> Option<&'static str> {
+        match self {
+            Self::System => None,
+            Self::Light | Self::Dark => Some(self.name()),
+        }
+    }
+
+    /// Returns the theme with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.name() == name)
+    }
+}
+
+/// (De)serialisation of durations as a number of seconds.
+///
+/// Durations are at least [`MIN_DURATION`] long, so that a zero in the file
+/// does not make the application refresh without pause.
+mod seconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize as _, Deserializer, Serializer};
+
+    /// The minimum duration.
+    const MIN_DURATION: Duration = Duration::from_secs(1);
+
+    /// Serialises a duration as a number of seconds.
+    pub fn serialize<S: Serializer>(
+        duration: &Duration,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_secs())
+    }
+
+    /// Deserialises a duration from a number of seconds.
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer)
+            .map(|seconds| Duration::from_secs(seconds).max(MIN_DURATION))
+    }
+}
+
+/// The storage of the settings in a configuration file.
+#[cfg(not(target_arch = "wasm32"))]
+mod storage {
+    use std::{
+        env, fs, io,
+        path::{Path, PathBuf},
+    };
+
+    use dioxus::prelude::*;
+
+    /// Reads the settings file, if any.
+    pub fn read() -> Option<String> {
+        let path = path()?;
+
+        fs::read_to_string(&path)
+            .inspect_err(|error| {
+                if error.kind() != io::ErrorKind::NotFound {
+                    warn!(%error, path = %path.display(), "failed to read the settings");
+                }
+            })
+            .ok()
+    }
+
+    /// Writes the settings file.
+    ///
+    /// As it contains the API token, it is only readable by the user on Unix.
+    pub fn write(contents: &str) {
+        let Some(path) = path() else {
+            warn!("no configuration directory, the settings are not saved");
+            return;
+        };
+
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|()| write_private(&path, contents));
+
+        if let Err(error) = result {
+            warn!(%error, path = %path.display(), "failed to save the settings");
+        }
+    }
+
+    /// Writes a file only readable by the user.
+    #[cfg(unix)]
+    fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+        use std::{
+            io::Write as _,
+            os::unix::fs::{OpenOptionsExt as _, PermissionsExt as _},
+        };
+
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?;
+
+        // NOTE: The mode is only applied on creation.
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(contents.as_bytes())
+    }
+
+    /// Writes a file.
+    #[cfg(not(unix))]
+    fn write_private(path: &Path, contents: &str) -> io::Result<()> {
+        fs::write(path, contents)
+    }
+
+    /// Returns the path of the settings file.
+    ///
+    /// This is `$XDG_CONFIG_HOME/ntf/app.toml`, where `XDG_CONFIG_HOME`
+    /// defaults to `~/.config`.
+    fn path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                env::var_os("HOME")
+                    .map(|home| PathBuf::from(home).join(".config"))
+            })?;
+
+        Some(config_home.join("ntf").join("app.toml"))
+    }
+}
+
+/// The storage of the settings in the local storage of the browser.
+#[cfg(target_arch = "wasm32")]
+mod storage {
+    use dioxus::prelude::*;
+    use web_sys::Storage;
+
+    /// The key of the settings in the local storage.
+    const KEY: &str = "ntf-app.settings";
+
+    /// Reads the settings from the local storage, if any.
+    pub fn read() -> Option<String> {
+        local_storage()?.get_item(KEY).ok()?
+    }
+
+    /// Writes the settings to the local storage.
+    pub fn write(contents: &str) {
+        let result =
+            local_storage().map(|storage| storage.set_item(KEY, contents));
+
+        if !matches!(result, Some(Ok(()))) {
+            warn!("failed to save the settings in the local storage");
+        }
+    }
+
+    /// Returns the local storage, if available.
+    fn local_storage() -> Option<Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn refresh_interval_is_at_least_a_second() {
+        let settings =
+            toml::from_str::<Settings>("refresh_interval = 0").unwrap();
+        assert_eq!(settings.refresh_interval, Duration::from_secs(1));
+
+        let settings =
+            toml::from_str::<Settings>("refresh_interval = 60").unwrap();
+        assert_eq!(settings.refresh_interval, Duration::from_secs(60));
+    }
+}
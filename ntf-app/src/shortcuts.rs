@@ -0,0 +1,199 @@
+//! The keyboard shortcuts.
+
+use dioxus::prelude::*;
+
+/// A keyboard shortcut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shortcut {
+    /// Select the next notification.
+    Next,
+    /// Select the previous notification.
+    Previous,
+    /// Open the selected notification.
+    Open,
+    /// Acknowledge the selected notification.
+    Ack,
+    /// Delete the selected notification.
+    Delete,
+    /// Focus the search field.
+    Search,
+    /// Refresh the notifications.
+    Refresh,
+    /// Go back, or close the help.
+    Back,
+    /// Show or hide the help.
+    Help,
+}
+
+impl Shortcut {
+    /// All the shortcuts.
+    pub const ALL: [Self; 9] = [
+        Self::Next,
+        Self::Previous,
+        Self::Open,
+        Self::Ack,
+        Self::Delete,
+        Self::Search,
+        Self::Refresh,
+        Self::Back,
+        Self::Help,
+    ];
+
+    /// Returns the shortcut triggered by a keyboard event, if any.
+    pub fn from_event(event: &KeyboardEvent) -> Option<Self> {
+        Self::from_key(&event.key(), event.modifiers())
+    }
+
+    /// Returns the shortcut triggered by a key, if any.
+    ///
+    /// Keys pressed with Ctrl, Alt or Meta are left to the platform.
+    pub fn from_key(key: &Key, modifiers: Modifiers) -> Option<Self> {
+        if modifiers
+            .intersects(Modifiers::CONTROL | Modifiers::ALT | Modifiers::META)
+        {
+            return None;
+        }
+
+        match key {
+            Key::ArrowDown => Some(Self::Next),
+            Key::ArrowUp => Some(Self::Previous),
+            Key::Enter => Some(Self::Open),
+            Key::Escape => Some(Self::Back),
+            Key::Character(character) => match character.as_str() {
+                "j" => Some(Self::Next),
+                "k" => Some(Self::Previous),
+                "a" => Some(Self::Ack),
+                "d" => Some(Self::Delete),
+                "/" => Some(Self::Search),
+                "r" => Some(Self::Refresh),
+                "?" => Some(Self::Help),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the keys of the shortcut, as shown in the help.
+    pub const fn keys(self) -> &'static [&'static str] {
+        match self {
+            Self::Next => &["j", "↓"],
+            Self::Previous => &["k", "↑"],
+            Self::Open => &["Enter"],
+            Self::Ack => &["a"],
+            Self::Delete => &["d"],
+            Self::Search => &["/"],
+            Self::Refresh => &["r"],
+            Self::Back => &["Esc"],
+            Self::Help => &["?"],
+        }
+    }
+
+    /// Returns the description of the shortcut, as shown in the help.
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::Next => "Select the next notification",
+            Self::Previous => "Select the previous notification",
+            Self::Open => "Open the selected notification",
+            Self::Ack => "Acknowledge the notification",
+            Self::Delete => "Delete the notification",
+            Self::Search => "Search",
+            Self::Refresh => "Refresh the notifications",
+            Self::Back => "Go back",
+            Self::Help => "Show this help",
+        }
+    }
+}
+
+/// Leaves the keys activating a focused button or link to it, instead of
+/// triggering the shortcuts of the page.
+#[expect(
+    clippy::needless_pass_by_value,
+    reason = "event handlers take their event"
+)]
+pub fn keep_activation_keys(event: KeyboardEvent) {
+    if is_activation_key(&event.key()) {
+        event.stop_propagation();
+    }
+}
+
+/// Returns whether a key activates a focused button or link.
+fn is_activation_key(key: &Key) -> bool {
+    match key {
+        Key::Enter => true,
+        Key::Character(character) => character == " ",
+        _ => false,
+    }
+}
+
+/// An overlay listing the keyboard shortcuts.
+#[component]
+pub fn ShortcutsHelp(
+    /// Whether the overlay is open.
+    mut open: Signal<bool>,
+) -> Element {
+    rsx! {
+        if open() {
+            div { class: "modal modal-open", onclick: move |_| open.set(false),
+                div { class: "modal-box",
+                    h3 { class: "text-lg font-bold", "Keyboard shortcuts" }
+                    table { class: "table",
+                        tbody {
+                            for shortcut in Shortcut::ALL {
+                                tr { key: "{shortcut:?}",
+                                    td {
+                                        for key in shortcut.keys() {
+                                            kbd { class: "kbd kbd-sm mr-1", "{key}" }
+                                        }
+                                    }
+                                    td { "{shortcut.description()}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn keys_map_to_shortcuts() {
+        let key = |key: &str| Key::Character(key.to_owned());
+
+        assert_eq!(
+            Shortcut::from_key(&key("j"), Modifiers::empty()),
+            Some(Shortcut::Next)
+        );
+        assert_eq!(
+            Shortcut::from_key(&Key::ArrowUp, Modifiers::empty()),
+            Some(Shortcut::Previous)
+        );
+        assert_eq!(
+            Shortcut::from_key(&key("?"), Modifiers::SHIFT),
+            Some(Shortcut::Help)
+        );
+        assert_eq!(Shortcut::from_key(&key("x"), Modifiers::empty()), None);
+    }
+
+    #[test]
+    fn modified_keys_are_ignored() {
+        let key = Key::Character(String::from("r"));
+
+        assert_eq!(Shortcut::from_key(&key, Modifiers::CONTROL), None);
+        assert_eq!(Shortcut::from_key(&key, Modifiers::META), None);
+    }
+
+    #[test]
+    fn enter_and_space_activate_controls() {
+        assert!(is_activation_key(&Key::Enter));
+        assert!(is_activation_key(&Key::Character(String::from(" "))));
+        assert!(!is_activation_key(&Key::ArrowDown));
+        assert!(!is_activation_key(&Key::Character(String::from("j"))));
+    }
+}
@@ -0,0 +1,469 @@
+//! The state shared by the components.
+
+use std::{collections::HashSet, time::Duration};
+
+use dioxus::{core::spawn_forever, prelude::*};
+use ntf_api::{ApiClient, ApiError, Notification, ResourceAccessError};
+#[cfg(not(target_arch = "wasm32"))]
+use ntf_api::{EventStream, NotificationEvent, WatchError};
+
+use crate::{
+    cache::{Cache, PendingAction},
+    settings::{SETTINGS, api_client},
+    toast::{show_error, show_undo},
+};
+
+/// The notifications fetched from the server, once loaded.
+pub static NOTIFICATIONS: GlobalSignal<Option<Vec<Notification>>> =
+    Signal::global(|| None);
+
+/// The error from the last refresh of the notifications, if it has failed.
+pub static LOAD_ERROR: GlobalSignal<Option<String>> = Signal::global(|| None);
+
+/// Whether the notifications come from the offline cache or a previous
+/// refresh, as the server cannot be reached.
+pub static STALE: GlobalSignal<bool> = Signal::global(|| false);
+
+/// The actions made while offline, waiting for the server to be reachable.
+pub static QUEUE: GlobalSignal<Vec<PendingAction>> = Signal::global(Vec::new);
+
+/// The IDs of the notifications received since the application started, until
+/// they are seen.
+pub static NEW_NOTIFICATIONS: GlobalSignal<HashSet<usize>> =
+    Signal::global(HashSet::new);
+
+/// Keeps the notifications up to date.
+///
+/// The server pushes the changes when it supports it. Otherwise, the
+/// notifications are polled every [`Settings::refresh_interval`].
+///
+/// The notifications are fetched again from scratch when the server changes.
+///
+/// [`Settings::refresh_interval`]: crate::settings::Settings::refresh_interval
+pub fn use_live_notifications() {
+    let server = use_memo(|| {
+        let settings = SETTINGS.read();
+        (settings.server_url.clone(), settings.token.clone())
+    });
+
+    use_resource(move || {
+        server.read();
+        live_notifications()
+    });
+
+    use_effect(|| {
+        let Some(notifications) = NOTIFICATIONS.read().clone() else {
+            return;
+        };
+
+        let cache = Cache {
+            server_url: SETTINGS.peek().server_url.clone(),
+            notifications,
+            queue: QUEUE.read().clone(),
+        };
+
+        spawn(async move { cache.save().await });
+    });
+}
+
+/// Fetches the notifications from the server, after replaying the pending
+/// actions.
+///
+/// The notifications that were not there before are marked as new. On error,
+/// the previous notifications are kept as stale and the error is set in
+/// [`LOAD_ERROR`].
+pub async fn refresh_notifications() {
+    let api = api_client();
+    replay_queue(&api).await;
+
+    let mut notifications = match api.list_notifications().await {
+        Ok(notifications) => notifications,
+        Err(error) => {
+            warn!(%error, "failed to fetch the notifications");
+            *LOAD_ERROR.write() = Some(error.to_string());
+            *STALE.write() = NOTIFICATIONS.peek().is_some();
+            return;
+        }
+    };
+
+    // NOTE: Actions still pending could not be replayed, but must be shown.
+    for action in QUEUE.peek().iter() {
+        action.apply(&mut notifications);
+    }
+
+    if LOAD_ERROR.peek().is_some() {
+        *LOAD_ERROR.write() = None;
+    }
+
+    if *STALE.peek() {
+        *STALE.write() = false;
+    }
+
+    let mut current = NOTIFICATIONS.write();
+
+    if let Some(previous) = current.as_deref() {
+        let known = previous.iter().map(|ntf| ntf.id).collect::<HashSet<_>>();
+        NEW_NOTIFICATIONS.write().extend(
+            notifications
+                .iter()
+                .map(|ntf| ntf.id)
+                .filter(|id| !known.contains(id)),
+        );
+    }
+
+    *current = Some(notifications);
+}
+
+/// Acknowledges a notification.
+///
+/// The notification is shown as acknowledged right away, and restored if the
+/// server fails to acknowledge it. If the server cannot be reached, the action
+/// is queued to be replayed later.
+pub async fn ack_notification(id: usize) {
+    // NOTE: The notification may not be in the list yet, when shown directly.
+    let previous = update(id, |ntf| ntf.ack = true);
+
+    if !QUEUE.peek().is_empty() {
+        QUEUE.write().push(PendingAction::Ack(id));
+        return;
+    }
+
+    match api_client().ack_notification(id).await {
+        Ok(notification) => {
+            update(id, |ntf| *ntf = notification);
+        }
+        Err(error) if is_offline(&error) => {
+            QUEUE.write().push(PendingAction::Ack(id));
+        }
+        Err(ResourceAccessError::NotFound { .. }) => {
+            remove(id);
+            show_error(format!("#{id} has been deleted elsewhere."));
+        }
+        Err(error) => {
+            if let Some(previous) = previous {
+                update(id, |ntf| *ntf = previous);
+            }
+            show_error(format!("Failed to acknowledge #{id}: {error}"));
+        }
+    }
+}
+
+/// Deletes a notification.
+///
+/// The notification is removed right away, and restored at its previous place
+/// if the server fails to delete it. If the server cannot be reached, the
+/// action is queued to be replayed later. Once deleted, a toast allows to
+/// restore it from the trash.
+pub async fn delete_notification(id: usize) {
+    let removed = remove(id);
+
+    if !QUEUE.peek().is_empty() {
+        QUEUE.write().push(PendingAction::Delete(id));
+        offer_restore(id, removed);
+        return;
+    }
+
+    match api_client().delete_notification(id).await {
+        Ok(_) => offer_restore(id, removed),
+        // NOTE: If it has been deleted elsewhere, the result is the same.
+        Err(ResourceAccessError::NotFound { .. }) => {}
+        Err(error) if is_offline(&error) => {
+            QUEUE.write().push(PendingAction::Delete(id));
+            offer_restore(id, removed);
+        }
+        Err(error) => {
+            if let Some((index, notification)) = removed {
+                insert(index, notification);
+            }
+            show_error(format!("Failed to delete #{id}: {error}"));
+        }
+    }
+}
+
+/// Shows a toast allowing to restore a deleted notification.
+fn offer_restore(id: usize, removed: Option<(usize, Notification)>) {
+    show_undo(format!("#{id} deleted."), move || {
+        spawn_forever(restore_notification(id, removed.clone()));
+    });
+}
+
+/// Restores a deleted notification, at its previous index if known.
+///
+/// If the deletion is still pending, it is simply dropped from the queue.
+async fn restore_notification(
+    id: usize,
+    removed: Option<(usize, Notification)>,
+) {
+    let pending = QUEUE
+        .peek()
+        .iter()
+        .position(|action| *action == PendingAction::Delete(id));
+
+    if let Some(position) = pending {
+        QUEUE.write().remove(position);
+        if let Some((index, notification)) = removed {
+            insert(index, notification);
+        }
+        return;
+    }
+
+    match api_client().restore_notification(id).await {
+        Ok(notification) => {
+            let index = removed.map_or(usize::MAX, |(index, _)| index);
+            insert(index, notification);
+        }
+        Err(error) => show_error(format!("Failed to restore #{id}: {error}")),
+    }
+}
+
+/// Runs an action of a notification on the server.
+///
+/// The notification is updated in the list with the outcome of the action, and
+/// returned. Failures are reported with a toast.
+pub async fn run_action(id: usize, name: &str) -> Option<Notification> {
+    let notification = match api_client().run_action(id, name).await {
+        Ok(notification) => notification,
+        Err(error) => {
+            show_error(format!("Failed to run `{name}` on #{id}: {error}"));
+            return None;
+        }
+    };
+
+    let failure = notification
+        .actions
+        .iter()
+        .find(|action| action.name == name)
+        .and_then(|action| Some((action, action.outcome.as_ref()?)))
+        .filter(|(_, outcome)| !outcome.is_success());
+
+    if let Some((action, outcome)) = failure {
+        let reason = match (&outcome.error, outcome.status) {
+            (Some(error), _) => error.clone(),
+            (None, Some(status)) => format!("HTTP status {status}"),
+            (None, None) => String::from("unknown error"),
+        };
+        show_error(format!("`{}` failed on #{id}: {reason}", action.label));
+    }
+
+    update(id, |existing| *existing = notification.clone());
+    Some(notification)
+}
+
+/// Replays the pending actions, until the server cannot be reached.
+///
+/// Acknowledging a notification deleted elsewhere in the meantime is a
+/// conflict: the action is dropped and the user is told about it.
+async fn replay_queue(api: &ApiClient) {
+    loop {
+        let Some(action) = QUEUE.peek().first().copied() else {
+            return;
+        };
+
+        let result = match action {
+            PendingAction::Ack(id) => api.ack_notification(id).await.map(drop),
+            PendingAction::Delete(id) => {
+                api.delete_notification(id).await.map(drop)
+            }
+        };
+
+        match (action, result) {
+            (_, Err(error)) if is_offline(&error) => return,
+            (_, Ok(()))
+            | (
+                PendingAction::Delete(_),
+                Err(ResourceAccessError::NotFound { .. }),
+            ) => {}
+            (
+                PendingAction::Ack(id),
+                Err(ResourceAccessError::NotFound { .. }),
+            ) => {
+                show_error(format!(
+                    "#{id} has been deleted elsewhere, it could not be acknowledged."
+                ));
+            }
+            (_, Err(error)) => {
+                show_error(format!(
+                    "Failed to replay an offline action: {error}"
+                ));
+            }
+        }
+
+        QUEUE.write().remove(0);
+    }
+}
+
+/// Returns whether an error is due to the server not being reachable.
+const fn is_offline(error: &ResourceAccessError) -> bool {
+    matches!(
+        error,
+        ResourceAccessError::ApiError(ApiError::ConnectionError { .. })
+    )
+}
+
+/// Removes a notification from the list.
+///
+/// Returns its previous index and the notification, or `None` if it is not in
+/// the list.
+fn remove(id: usize) -> Option<(usize, Notification)> {
+    let mut notifications = NOTIFICATIONS.write();
+    let notifications = notifications.as_mut()?;
+    let index = notifications.iter().position(|ntf| ntf.id == id)?;
+    Some((index, notifications.remove(index)))
+}
+
+/// Inserts back a notification in the list, at its previous index if possible.
+///
+/// Nothing happens if the notification is already there, for instance when it
+/// has been restored by a pushed event.
+fn insert(index: usize, notification: Notification) {
+    if let Some(notifications) = NOTIFICATIONS.write().as_mut() {
+        if notifications.iter().any(|ntf| ntf.id == notification.id) {
+            return;
+        }
+
+        let index = index.min(notifications.len());
+        notifications.insert(index, notification);
+    }
+}
+
+/// Updates a notification in place.
+///
+/// Returns the notification as it was before the update, or `None` if it is
+/// not in the list.
+fn update(
+    id: usize,
+    change: impl FnOnce(&mut Notification),
+) -> Option<Notification> {
+    let mut notifications = NOTIFICATIONS.write();
+    let notification = notifications
+        .as_mut()?
+        .iter_mut()
+        .find(|ntf| ntf.id == id)?;
+
+    let previous = notification.clone();
+    change(notification);
+    Some(previous)
+}
+
+/// Marks a notification as seen, so it is not highlighted anymore.
+pub fn mark_seen(id: usize) {
+    if NEW_NOTIFICATIONS.peek().contains(&id) {
+        NEW_NOTIFICATIONS.write().remove(&id);
+    }
+}
+
+/// Updates the notifications until the application stops.
+#[expect(clippy::infinite_loop, reason = "runs until the application stops")]
+async fn live_notifications() {
+    *NOTIFICATIONS.write() = None;
+    *LOAD_ERROR.write() = None;
+    *STALE.write() = false;
+    QUEUE.write().clear();
+    NEW_NOTIFICATIONS.write().clear();
+
+    let server_url = SETTINGS.peek().server_url.clone();
+    if let Some(cache) = Cache::load()
+        .await
+        .filter(|cache| cache.server_url == server_url)
+    {
+        *NOTIFICATIONS.write() = Some(cache.notifications);
+        *QUEUE.write() = cache.queue;
+        *STALE.write() = true;
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut push = true;
+
+    loop {
+        #[cfg(not(target_arch = "wasm32"))]
+        if push {
+            push = watch().await;
+        }
+
+        refresh_notifications().await;
+
+        let interval = SETTINGS.peek().refresh_interval;
+        sleep(interval).await;
+    }
+}
+
+/// Applies the events pushed by the server until the connection is lost.
+///
+/// Returns whether the server supports pushing events.
+#[cfg(not(target_arch = "wasm32"))]
+async fn watch() -> bool {
+    match api_client().watch_notifications().await {
+        Ok(stream) => follow(stream).await,
+        Err(WatchError::Unsupported) => {
+            info!("the server cannot push events, polling instead");
+            return false;
+        }
+        Err(WatchError::ApiError(error)) => {
+            warn!(%error, "failed to watch the notifications");
+        }
+    }
+
+    true
+}
+
+/// Applies the events from a stream until the connection is lost.
+#[cfg(not(target_arch = "wasm32"))]
+async fn follow(mut stream: EventStream) {
+    // NOTE: Refresh once connected, not to miss changes made in between.
+    refresh_notifications().await;
+
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(event) => apply(event),
+            Err(error) => {
+                warn!(%error, "lost the connection to the server");
+                break;
+            }
+        }
+    }
+}
+
+/// Applies an event pushed by the server to the notifications.
+#[cfg(not(target_arch = "wasm32"))]
+fn apply(event: NotificationEvent) {
+    let mut notifications = NOTIFICATIONS.write();
+    let Some(notifications) = notifications.as_mut() else {
+        return;
+    };
+
+    match event {
+        NotificationEvent::Created(notification) => {
+            // NOTE: The notification may already be there if it has been
+            // created while refreshing after subscribing.
+            if !notifications.iter().any(|ntf| ntf.id == notification.id) {
+                NEW_NOTIFICATIONS.write().insert(notification.id);
+                notifications.push(notification);
+            }
+        }
+        NotificationEvent::Acked(notification) => {
+            if let Some(existing) = notifications
+                .iter_mut()
+                .find(|ntf| ntf.id == notification.id)
+            {
+                *existing = notification;
+            }
+        }
+        NotificationEvent::Deleted(notification) => {
+            notifications.retain(|ntf| ntf.id != notification.id);
+        }
+        NotificationEvent::Restored(notification) => {
+            if !notifications.iter().any(|ntf| ntf.id == notification.id) {
+                notifications.push(notification);
+            }
+        }
+    }
+}
+
+/// Waits for the given duration.
+pub async fn sleep(duration: Duration) {
+    #[cfg(target_arch = "wasm32")]
+    gloo_timers::future::sleep(duration).await;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    tokio::time::sleep(duration).await;
+}
@@ -0,0 +1,121 @@
+//! Transient messages shown on top of the pages.
+
+use std::{fmt, rc::Rc, time::Duration};
+
+use dioxus::{core::spawn_forever, prelude::*};
+
+use crate::state::sleep;
+
+/// The time a toast stays on screen.
+pub const TOAST_DURATION: Duration = Duration::from_secs(5);
+
+/// The toasts currently shown.
+static TOASTS: GlobalSignal<Vec<Toast>> = Signal::global(Vec::new);
+
+/// The ID of the next toast.
+static NEXT_ID: GlobalSignal<usize> = Signal::global(|| 0);
+
+/// A transient message.
+#[derive(Debug, Clone)]
+struct Toast {
+    /// The ID of the toast.
+    id: usize,
+    /// The message to show.
+    message: String,
+    /// Whether the message is an error.
+    error: bool,
+    /// The action to undo what the message is about, if any.
+    undo: Option<Undo>,
+}
+
+/// An action undoing something.
+#[derive(Clone)]
+struct Undo(Rc<dyn Fn()>);
+
+/// Shows an error message for a few seconds.
+pub fn show_error(message: String) {
+    show(Toast {
+        id: next_id(),
+        message,
+        error: true,
+        undo: None,
+    });
+}
+
+/// Shows a message with an Undo button for a few seconds.
+pub fn show_undo(message: String, undo: impl Fn() + 'static) {
+    show(Toast {
+        id: next_id(),
+        message,
+        error: false,
+        undo: Some(Undo(Rc::new(undo))),
+    });
+}
+
+/// Shows a toast for a few seconds.
+fn show(toast: Toast) {
+    let id = toast.id;
+    TOASTS.write().push(toast);
+
+    spawn_forever(async move {
+        sleep(TOAST_DURATION).await;
+        dismiss(id);
+    });
+}
+
+/// Returns the ID of the next toast.
+fn next_id() -> usize {
+    let id = *NEXT_ID.peek();
+    *NEXT_ID.write() += 1;
+    id
+}
+
+/// Removes a toast.
+fn dismiss(id: usize) {
+    TOASTS.write().retain(|toast| toast.id != id);
+}
+
+/// The toasts currently shown.
+#[component]
+pub fn Toasts() -> Element {
+    rsx! {
+        div { class: "toast toast-end z-1000",
+            for toast in TOASTS.read().iter() {
+                div {
+                    key: "{toast.id}",
+                    role: "alert",
+                    class: "alert",
+                    class: if toast.error { "alert-error" } else { "alert-info" },
+                    span { "{toast.message}" }
+                    if let Some(Undo(undo)) = &toast.undo {
+                        button {
+                            class: "btn btn-sm",
+                            onclick: {
+                                let (id, undo) = (toast.id, Rc::clone(undo));
+                                move |_| {
+                                    undo();
+                                    dismiss(id);
+                                }
+                            },
+                            "Undo"
+                        }
+                    }
+                    button {
+                        class: "btn btn-sm btn-ghost",
+                        onclick: {
+                            let id = toast.id;
+                            move |_| dismiss(id)
+                        },
+                        "✗"
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Undo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Undo")
+    }
+}
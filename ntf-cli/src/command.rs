@@ -1,22 +1,63 @@
 //! The Command Line Interface for ntf-cli.
 
 mod ack;
+mod completions;
 mod create;
 mod delete;
+mod export;
 mod get;
 mod hello;
 mod helpers;
+mod import;
 mod list;
+mod man;
+mod output;
+mod targets;
+mod tui;
+mod watch;
 
-use clap::{ArgAction, Parser, Subcommand};
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::{
+    ArgAction, Args, CommandFactory as _, FromArgMatches as _, Parser,
+    Subcommand,
+};
+use clap_complete::CompleteEnv;
 use eyre::{Report, Result};
 use tracing_subscriber::fmt::format::FmtSpan;
 
+use ntf_api::{
+    ApiClient, ApiError, CreateError, CreateNotificationError,
+    CreateNotificationPayload, ListError, ResourceAccessError,
+    export::ExportError,
+};
+
+use crate::{
+    config::{Config, ConfigError, Profile},
+    error, hint,
+};
+
 use self::{
-    ack::Ack, create::Create, delete::Delete, get::Get, hello::Hello,
+    ack::Ack,
+    completions::{COMPLETE_VAR, Completions},
+    create::{Create, TemplateError},
+    delete::Delete,
+    export::Export,
+    get::Get,
+    hello::Hello,
+    import::Import,
     list::List,
+    man::Man,
+    targets::BatchError,
+    tui::Tui,
+    watch::Watch,
 };
 
+pub use self::output::OutputFormat;
+
+/// The default API base URL.
+const DEFAULT_URL: &str = "http://localhost:3000";
+
 /// The long version information.
 const LONG_VERSION: &str = concat!(
     env!("CARGO_PKG_VERSION"),
@@ -43,11 +84,34 @@ pub struct NtfCli {
     /// The command to run.
     #[command(subcommand)]
     command: NtfCliCommand,
+    /// Options shared by all commands.
+    #[command(flatten)]
+    global: GlobalArgs,
     /// The verbosity level.
     #[arg(short = 'v', action = ArgAction::Count, global = true)]
     verbosity: u8,
 }
 
+/// Options shared by all commands.
+#[derive(Debug, Args)]
+pub struct GlobalArgs {
+    /// The API base URL [default: localhost:3000].
+    #[arg(long, env = "NTF_URL", global = true)]
+    url: Option<String>,
+    /// The API token.
+    #[arg(long, env = "NTF_TOKEN", global = true, hide_env_values = true)]
+    token: Option<String>,
+    /// The configuration profile to use.
+    #[arg(long, env = "NTF_PROFILE", global = true)]
+    profile: Option<String>,
+    /// The configuration file [default: ~/.config/ntf/config.toml].
+    #[arg(long, env = "NTF_CONFIG", global = true)]
+    config: Option<PathBuf>,
+    /// The output format [default: text].
+    #[arg(short, long, value_enum, global = true)]
+    output: Option<OutputFormat>,
+}
+
 /// The subcommands of `ntf-cli`.
 #[derive(Debug, Subcommand)]
 pub enum NtfCliCommand {
@@ -63,37 +127,156 @@ pub enum NtfCliCommand {
     Ack(Ack),
     /// Deletes a notification.
     Delete(Delete),
+    /// Exports the notifications.
+    Export(Export),
+    /// Imports notifications from an export.
+    Import(Import),
+    /// Watches the notifications as they change.
+    Watch(Watch),
+    /// Opens an interactive inbox.
+    Tui(Tui),
+    /// Generates shell completions.
+    Completions(Completions),
+    /// Generates man pages.
+    Man(Man),
+}
+
+/// The settings resolved from the command line, environment and configuration.
+#[derive(Debug)]
+pub struct Settings {
+    /// The API base URL.
+    base_url: String,
+    /// The API token.
+    token: Option<String>,
+    /// The output format.
+    output: OutputFormat,
+    /// The notification templates.
+    templates: BTreeMap<String, CreateNotificationPayload>,
 }
 
 /// A command.
 trait Command {
     /// Runs the command.
-    async fn run(&self) -> Result<()>;
+    async fn run(&self, settings: &Settings) -> Result<()>;
 }
 
 impl NtfCli {
     /// Runs ntf-cli.
     pub async fn run() -> Result<()> {
+        CompleteEnv::with_factory(Self::command)
+            .var(COMPLETE_VAR)
+            .complete();
+
         let args = Self::parse();
         setup_tracing(args.verbosity);
 
-        match args.command.run().await {
+        match args.run_command().await {
             Err(error) => handle_errors(error),
             Ok(()) => Ok(()),
         }
     }
+
+    /// Resolves the settings and runs the command.
+    ///
+    /// The configuration file is only loaded for the commands talking to the
+    /// API, so that the others still work when it is broken.
+    async fn run_command(&self) -> Result<()> {
+        let settings = if self.command.uses_api() {
+            self.global.settings()?
+        } else {
+            self.global.settings_without_config()
+        };
+        tracing::debug!(?settings, "resolved the settings");
+        self.command.run(&settings).await
+    }
 }
 
 impl NtfCliCommand {
     /// Runs the given command.
-    pub async fn run(&self) -> Result<()> {
+    pub async fn run(&self, settings: &Settings) -> Result<()> {
+        match self {
+            Self::Hello(hello) => hello.run(settings).await,
+            Self::List(list) => list.run(settings).await,
+            Self::Create(create) => create.run(settings).await,
+            Self::Get(get) => get.run(settings).await,
+            Self::Ack(ack) => ack.run(settings).await,
+            Self::Delete(delete) => delete.run(settings).await,
+            Self::Export(export) => export.run(settings).await,
+            Self::Import(import) => import.run(settings).await,
+            Self::Watch(watch) => watch.run(settings).await,
+            Self::Tui(tui) => tui.run(settings).await,
+            Self::Completions(completions) => completions.run(settings).await,
+            Self::Man(man) => man.run(settings).await,
+        }
+    }
+
+    /// Returns whether the command talks to the API.
+    const fn uses_api(&self) -> bool {
         match self {
-            Self::Hello(hello) => hello.run().await,
-            Self::List(list) => list.run().await,
-            Self::Create(create) => create.run().await,
-            Self::Get(get) => get.run().await,
-            Self::Ack(ack) => ack.run().await,
-            Self::Delete(delete) => delete.run().await,
+            Self::List(_)
+            | Self::Create(_)
+            | Self::Get(_)
+            | Self::Ack(_)
+            | Self::Delete(_)
+            | Self::Export(_)
+            | Self::Import(_)
+            | Self::Watch(_)
+            | Self::Tui(_) => true,
+            Self::Hello(_) | Self::Completions(_) | Self::Man(_) => false,
+        }
+    }
+}
+
+impl GlobalArgs {
+    /// Parses the global options from the environment only.
+    fn from_env() -> Result<Self, clap::Error> {
+        let command = Self::augment_args(clap::Command::new("ntf-cli"));
+        Self::from_arg_matches(&command.try_get_matches_from(["ntf-cli"])?)
+    }
+
+    /// Resolves the settings.
+    ///
+    /// Options from the command line and the environment take precedence over
+    /// the selected profile of the configuration file.
+    fn settings(&self) -> Result<Settings, ConfigError> {
+        let config = Config::load(self.config.as_deref())?;
+        let profile = config.profile(self.profile.as_deref())?;
+
+        Ok(self.resolve(profile, config.templates().clone()))
+    }
+
+    /// Resolves the settings from the command line and the environment only.
+    fn settings_without_config(&self) -> Settings {
+        self.resolve(Profile::default(), BTreeMap::new())
+    }
+
+    /// Resolves the settings given a profile and templates.
+    fn resolve(
+        &self,
+        profile: Profile,
+        templates: BTreeMap<String, CreateNotificationPayload>,
+    ) -> Settings {
+        Settings {
+            base_url: self
+                .url
+                .clone()
+                .or(profile.url)
+                .unwrap_or_else(|| DEFAULT_URL.to_owned()),
+            token: self.token.clone().or(profile.token),
+            output: self.output.or(profile.output).unwrap_or_default(),
+            templates,
+        }
+    }
+}
+
+impl Settings {
+    /// Builds an API client from the settings.
+    fn api_client(&self) -> ApiClient {
+        let api = ApiClient::new(&self.base_url);
+
+        match &self.token {
+            Some(token) => api.with_token(token),
+            None => api,
         }
     }
 }
@@ -124,43 +307,185 @@ fn span_events(verbosity: u8) -> FmtSpan {
     }
 }
 
-// /// How to handle the error.
-// enum ErrorHandling {
-//     /// Return the report.
-//     Return(Report),
-//     /// Exit the program with the given status code.
-//     Exit(i32),
-// }
+/// How to handle the error.
+enum ErrorHandling {
+    /// Return the report.
+    Return,
+    /// Exit the program with the given status code.
+    Exit(i32),
+}
 
 /// Handles typical usage errors to enhance their output.
 fn handle_errors(error: Report) -> Result<()> {
-    // let handling = if let Some(error) = error.downcast_ref::<ErrorType>() {
-    //     handle_error_type(error)
-    // } else {
-    //     ErrorHandling::Return(error)
-    // };
-
-    // match handling {
-    //     ErrorHandling::Return(error) => Err(error),
-    //     ErrorHandling::Exit(code) => {
-    //         #[expect(
-    //             clippy::exit,
-    //             reason = "this function is purposefully written to handle \
-    //                 errors, write a useful message and exit with an error code"
-    //         )]
-    //         std::process::exit(code);
-    //     }
-    // }
-    Err(error)
-}
-
-// fn handle_error_type(error: &ErrorType) -> ErrorHandling {
-//     match error {
-//         ErrorType::ErrorKind => {
-//             error!("{error}");
-//             hint!("Some help message.");
-//         }
-//     }
-
-//     ErrorHandling::Exit(exitcode::USAGE)
-// }
+    let handling =
+        if let Some(error) = error.downcast_ref::<ResourceAccessError>() {
+            handle_resource_access_error(error)
+        } else if let Some(error) = error.downcast_ref::<CreateError>() {
+            handle_create_error(error)
+        } else if let Some(error) = error.downcast_ref::<ListError>() {
+            handle_list_error(error)
+        } else if let Some(error) = error.downcast_ref::<ConfigError>() {
+            handle_config_error(error)
+        } else if let Some(error) = error.downcast_ref::<TemplateError>() {
+            handle_template_error(error)
+        } else if let Some(error) = error.downcast_ref::<ExportError>() {
+            handle_export_error(error)
+        } else if let Some(error) = error.downcast_ref::<BatchError>() {
+            handle_batch_error(error)
+        } else if let Some(error) = error.downcast_ref::<ApiError>() {
+            handle_api_error(error)
+        } else {
+            ErrorHandling::Return
+        };
+
+    match handling {
+        ErrorHandling::Return => Err(error),
+        ErrorHandling::Exit(code) => {
+            #[expect(
+                clippy::exit,
+                reason = "this function is purposefully written to handle \
+                    errors, write a useful message and exit with an error code"
+            )]
+            std::process::exit(code);
+        }
+    }
+}
+
+/// Handles errors occurring when accessing a notification.
+fn handle_resource_access_error(error: &ResourceAccessError) -> ErrorHandling {
+    match error {
+        ResourceAccessError::ApiError(error) => handle_api_error(error),
+        ResourceAccessError::NotFound { id } => {
+            error!("Notification #{id} not found.");
+            hint!("Run `ntf-cli list` to see the available notifications.");
+            ErrorHandling::Exit(exitcode::NOINPUT)
+        }
+        ResourceAccessError::ActionNotFound { id, name } => {
+            error!("Notification #{id} has no action `{name}`.");
+            hint!("Run `ntf-cli get {id}` to see its actions.");
+            ErrorHandling::Exit(exitcode::NOINPUT)
+        }
+    }
+}
+
+/// Handles errors occurring when creating a notification.
+fn handle_create_error(error: &CreateError) -> ErrorHandling {
+    match error {
+        CreateError::ApiError(error) => handle_api_error(error),
+        CreateError::PayloadError(
+            error @ CreateNotificationError::RateLimited { .. },
+        ) => {
+            error!("The server has rejected the notification: {error}.");
+            hint!("Too many notifications have been sent, wait and try again.");
+            ErrorHandling::Exit(exitcode::TEMPFAIL)
+        }
+        CreateError::ValidationErrors(errors) => {
+            error!("The server has rejected the notification:");
+            for error in errors {
+                error!("  {}: {}.", error.field, error.message);
+            }
+            hint!("Fix the invalid fields and try again.");
+            ErrorHandling::Exit(exitcode::DATAERR)
+        }
+        CreateError::PayloadError(error) => {
+            error!("The server has rejected the notification: {error}.");
+            hint!("Check the content of the notification and try again.");
+            ErrorHandling::Exit(exitcode::DATAERR)
+        }
+    }
+}
+
+/// Handles errors occurring when listing notifications.
+fn handle_list_error(error: &ListError) -> ErrorHandling {
+    match error {
+        ListError::ApiError(error) => handle_api_error(error),
+    }
+}
+
+/// Handles errors occurring when reading or writing an export.
+fn handle_export_error(error: &ExportError) -> ErrorHandling {
+    match error {
+        ExportError::Io(_) => ErrorHandling::Return,
+        ExportError::Json(_) | ExportError::Csv(_) => {
+            error!("The file is not a valid export: {error}.");
+            hint!("Check the file, or set its format with `--format`.");
+            ErrorHandling::Exit(exitcode::DATAERR)
+        }
+    }
+}
+
+/// Handles errors occurring when loading the configuration.
+fn handle_config_error(error: &ConfigError) -> ErrorHandling {
+    match error {
+        ConfigError::ReadError { .. } | ConfigError::ParseError { .. } => {
+            return ErrorHandling::Return;
+        }
+        ConfigError::UnknownProfile { name, available } => {
+            error!("Unknown profile `{name}`.");
+
+            if available.is_empty() {
+                hint!("No profile is defined in the configuration file.");
+            } else {
+                let available = available.join(", ");
+                hint!("Available profiles: {available}.");
+            }
+        }
+    }
+
+    ErrorHandling::Exit(exitcode::CONFIG)
+}
+
+/// Handles errors occurring when rendering a template.
+fn handle_template_error(error: &TemplateError) -> ErrorHandling {
+    match error {
+        TemplateError::UnknownTemplate { name, available } => {
+            error!("Unknown template `{name}`.");
+
+            if available.is_empty() {
+                hint!("No template is defined in the configuration file.");
+            } else {
+                let available = available.join(", ");
+                hint!("Available templates: {available}.");
+            }
+
+            ErrorHandling::Exit(exitcode::CONFIG)
+        }
+        TemplateError::MissingVariable { name, .. } => {
+            error!("The template requires the `{name}` variable.");
+            hint!("Set it with `--var {name}=VALUE`.");
+            ErrorHandling::Exit(exitcode::USAGE)
+        }
+        TemplateError::UnclosedPlaceholder { template } => {
+            error!("The template `{template}` has an unclosed placeholder.");
+            ErrorHandling::Exit(exitcode::CONFIG)
+        }
+    }
+}
+
+/// Handles partial failures when operating on several notifications.
+fn handle_batch_error(error: &BatchError) -> ErrorHandling {
+    let BatchError { failed, total } = error;
+    error!("{failed} of {total} operations have failed.");
+
+    // NOTE: There is no sysexits code for partial failures.
+    ErrorHandling::Exit(1)
+}
+
+/// Handles errors occurring during any API request.
+fn handle_api_error(error: &ApiError) -> ErrorHandling {
+    match error {
+        ApiError::ConnectionError { base_url, .. } => {
+            error!("Could not connect to the server.");
+            hint!("Is ntf-srv running at {base_url}?");
+            ErrorHandling::Exit(exitcode::UNAVAILABLE)
+        }
+        ApiError::Unauthorised => {
+            error!("The server has refused the request as unauthorised.");
+            hint!("Check that you are allowed to access this server.");
+            ErrorHandling::Exit(exitcode::NOPERM)
+        }
+        ApiError::RequestError(_) | ApiError::ResponseError(_) => {
+            ErrorHandling::Return
+        }
+    }
+}
@@ -3,30 +3,34 @@
 use clap::Parser;
 use eyre::Result;
 
-use ntf_api::ApiClient;
+use super::{Settings, targets::Targets};
 
 /// Arguments for `ntf-cli ack`.
 #[derive(Debug, Parser)]
 pub struct Ack {
-    /// ID of the notification to acknowledge.
-    id: usize,
-    /// The API base URL.
-    #[arg(long = "url", default_value = "http://localhost:3000")]
-    base_url: String,
+    /// The notifications to acknowledge.
+    #[command(flatten)]
+    targets: Targets,
 }
 
 impl super::Command for Ack {
     #[tracing::instrument(name = "ack", level = "trace", skip_all)]
-    async fn run(&self) -> Result<()> {
+    async fn run(&self, settings: &Settings) -> Result<()> {
         tracing::info!(params = ?self, "running ack");
 
-        let Self { id, base_url } = self;
-
-        let api = ApiClient::new(base_url);
-        let notification = api.ack_notification(*id).await?;
-
-        println!("acknowledged: {notification:?}");
-
-        Ok(())
+        let Self { targets } = self;
+
+        let api = settings.api_client();
+        targets
+            .run(
+                |id| api.ack_notification(id),
+                |notification| {
+                    settings.output.print(
+                        notification,
+                        format_args!("acknowledged: {notification:?}"),
+                    )
+                },
+            )
+            .await
     }
 }
@@ -0,0 +1,116 @@
+//! The `completions` subcommand.
+
+use std::{ffi::OsStr, io, thread, time::Duration};
+
+use clap::{CommandFactory as _, Parser};
+use clap_complete::{CompletionCandidate, Shell, env::Shells};
+use eyre::{Result, eyre};
+
+use super::{GlobalArgs, NtfCli, Settings};
+
+/// The name of the environment variable triggering dynamic completions.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// The maximum time to wait for the server when completing IDs.
+const ID_COMPLETION_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Arguments for `ntf-cli completions`.
+#[derive(Debug, Parser)]
+pub struct Completions {
+    /// The shell to generate the completions for.
+    shell: Shell,
+    /// Generate a script calling ntf-cli to complete notification IDs.
+    ///
+    /// Dynamic completions query the server configured through the
+    /// environment and the configuration file to suggest notification IDs.
+    #[arg(long)]
+    dynamic: bool,
+}
+
+impl super::Command for Completions {
+    #[tracing::instrument(name = "completions", level = "trace", skip_all)]
+    async fn run(&self, _settings: &Settings) -> Result<()> {
+        tracing::info!(params = ?self, "running completions");
+
+        let Self { shell, dynamic } = self;
+
+        let mut command = NtfCli::command();
+        let bin_name = command.get_name().to_owned();
+
+        if *dynamic {
+            let shells = Shells::builtins();
+            let completer = shells
+                .completer(&shell.to_string())
+                .ok_or_else(|| eyre!("no dynamic completions for {shell}"))?;
+
+            completer.write_registration(
+                COMPLETE_VAR,
+                &bin_name,
+                &bin_name,
+                &bin_name,
+                &mut io::stdout(),
+            )?;
+        } else {
+            clap_complete::generate(
+                *shell,
+                &mut command,
+                bin_name,
+                &mut io::stdout(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Completes notification IDs with the ones available on the server.
+///
+/// The server is configured through the environment and the configuration
+/// file only, as the command line is not parsed at this point.
+pub fn complete_ids(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return vec![];
+    };
+
+    let Some(settings) = GlobalArgs::from_env()
+        .ok()
+        .and_then(|args| args.settings().ok())
+    else {
+        return vec![];
+    };
+
+    // NOTE: Completions are computed synchronously while the main runtime is
+    // running, so let’s use a dedicated one in its own thread.
+    let notifications = thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                let runtime = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .ok()?;
+
+                runtime.block_on(async {
+                    tokio::time::timeout(
+                        ID_COMPLETION_TIMEOUT,
+                        settings.api_client().list_notifications(),
+                    )
+                    .await
+                    .ok()?
+                    .ok()
+                })
+            })
+            .join()
+            .ok()
+            .flatten()
+    });
+
+    notifications
+        .unwrap_or_default()
+        .into_iter()
+        .map(|notification| (notification.id.to_string(), notification))
+        .filter(|(id, _)| id.starts_with(current))
+        .map(|(id, notification)| {
+            CompletionCandidate::new(id).help(Some(notification.message.into()))
+        })
+        .collect()
+}
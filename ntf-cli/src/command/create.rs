@@ -1,32 +1,422 @@
 //! The `create` subcommand.
 
-use clap::Parser;
-use eyre::Result;
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Read as _},
+    path::{Path, PathBuf},
+};
 
-use ntf_api::ApiClient;
+use clap::{Args, Parser};
+use eyre::{Result, eyre};
+use thiserror::Error;
+
+use ntf_api::{
+    Action, ActionKind, ApiClient, CreateNotificationPayload, Format, Priority,
+};
+
+use crate::error;
+
+use super::{Settings, targets::BatchError};
 
 /// Arguments for `ntf-cli create`.
 #[derive(Debug, Parser)]
 pub struct Create {
+    /// Where to take the notification from.
+    #[command(flatten)]
+    source: Source,
+    /// A tag to add to the notification (can be repeated).
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// The priority of the notification (min, low, normal, high or urgent).
+    #[arg(short, long)]
+    priority: Option<Priority>,
+    /// The format of the message (plain or markdown).
+    #[arg(long)]
+    format: Option<Format>,
+    /// A variable for the template, as `NAME=VALUE` (can be repeated).
+    #[arg(
+        long = "var",
+        value_name = "NAME=VALUE",
+        requires = "template",
+        value_parser = parse_var,
+    )]
+    vars: Vec<(String, String)>,
+}
+
+/// Errors that can occur when rendering a template.
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    /// The template is not defined in the configuration file.
+    #[error("unknown template `{name}`")]
+    UnknownTemplate {
+        /// The name of the template.
+        name: String,
+        /// The templates defined in the configuration file.
+        available: Vec<String>,
+    },
+    /// A placeholder has no matching variable.
+    #[error("missing variable `{name}` for `{template}`")]
+    MissingVariable {
+        /// The name of the variable.
+        name: String,
+        /// The template string.
+        template: String,
+    },
+    /// A placeholder is not closed.
+    #[error("unclosed placeholder in `{template}`")]
+    UnclosedPlaceholder {
+        /// The template string.
+        template: String,
+    },
+}
+
+/// Where to take the notification from.
+#[derive(Debug, Args)]
+#[group(required = true, multiple = false)]
+struct Source {
     /// The message of the notification.
-    message: String,
-    /// The API base URL.
-    #[arg(long = "url", default_value = "http://localhost:3000")]
-    base_url: String,
+    message: Option<String>,
+    /// Read the message from the standard input.
+    #[arg(long)]
+    stdin: bool,
+    /// Read the payload from a JSON or TOML file.
+    #[arg(long, value_name = "PATH")]
+    file: Option<PathBuf>,
+    /// Create one notification per line of the standard input.
+    #[arg(long)]
+    batch: bool,
+    /// Render the payload from a template of the configuration file.
+    #[arg(long, value_name = "NAME")]
+    template: Option<String>,
 }
 
 impl super::Command for Create {
     #[tracing::instrument(name = "create", level = "trace", skip_all)]
-    async fn run(&self) -> Result<()> {
+    async fn run(&self, settings: &Settings) -> Result<()> {
         tracing::info!(params = ?self, "running create");
 
-        let Self { message, base_url } = self;
+        let api = settings.api_client();
+
+        if self.source.batch {
+            return self.create_batch(settings, &api).await;
+        }
+
+        let mut payload = self.payload(settings)?;
+        self.apply_options(&mut payload);
+
+        create(settings, &api, &payload).await
+    }
+}
+
+impl Create {
+    /// Builds the payload from the source.
+    fn payload(
+        &self,
+        settings: &Settings,
+    ) -> Result<CreateNotificationPayload> {
+        let Source {
+            message,
+            stdin,
+            file,
+            template,
+            ..
+        } = &self.source;
+
+        if let Some(message) = message {
+            Ok(payload(message.clone()))
+        } else if *stdin {
+            let mut message = String::new();
+            io::stdin().read_to_string(&mut message)?;
+            Ok(payload(message.trim_end().to_owned()))
+        } else if let Some(path) = file {
+            read_payload(path)
+        } else if let Some(name) = template {
+            let template = settings.templates.get(name).ok_or_else(|| {
+                TemplateError::UnknownTemplate {
+                    name: name.clone(),
+                    available: settings.templates.keys().cloned().collect(),
+                }
+            })?;
+
+            let vars = self.vars.iter().cloned().collect();
+            Ok(render(template, &vars)?)
+        } else {
+            Err(eyre!("no message, file, template or standard input given"))
+        }
+    }
+
+    /// Applies the tags, priority and format given on the command line.
+    fn apply_options(&self, payload: &mut CreateNotificationPayload) {
+        payload.tags.extend(self.tags.iter().cloned());
 
-        let api = ApiClient::new(base_url);
-        let notification = api.create_notification(message).await?;
+        if let Some(priority) = self.priority {
+            payload.priority = priority;
+        }
+
+        if let Some(format) = self.format {
+            payload.format = format;
+        }
+    }
+
+    /// Creates one notification per line of the standard input.
+    async fn create_batch(
+        &self,
+        settings: &Settings,
+        api: &ApiClient,
+    ) -> Result<()> {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+
+        let lines = input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut failed = 0;
+
+        for line in &lines {
+            let mut payload = payload((*line).to_owned());
+            self.apply_options(&mut payload);
+
+            if let Err(error) = create(settings, api, &payload).await {
+                tracing::warn!(line, ?error, "creation failed");
+                error!("{line}: {error}");
+                failed += 1;
+            }
+        }
+
+        if failed == 0 {
+            Ok(())
+        } else {
+            Err(BatchError {
+                failed,
+                total: lines.len(),
+            }
+            .into())
+        }
+    }
+}
+
+/// Creates a notification and prints it.
+async fn create(
+    settings: &Settings,
+    api: &ApiClient,
+    payload: &CreateNotificationPayload,
+) -> Result<()> {
+    let notification = api.create_notification(payload).await?;
+
+    settings
+        .output
+        .print(&notification, format_args!("created: {notification:?}"))
+}
+
+/// Builds a payload with only a message.
+fn payload(message: String) -> CreateNotificationPayload {
+    CreateNotificationPayload {
+        message,
+        format: Format::default(),
+        tags: vec![],
+        priority: Priority::default(),
+        actions: vec![],
+    }
+}
 
-        println!("created: {notification:?}");
+/// Reads a payload from a JSON or TOML file, depending on its extension.
+fn read_payload(path: &Path) -> Result<CreateNotificationPayload> {
+    let contents = fs::read_to_string(path)?;
+
+    let payload = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str(&contents)?
+    } else {
+        serde_json::from_str(&contents)?
+    };
+
+    Ok(payload)
+}
+
+/// Renders a template by replacing the `{name}` placeholders with variables.
+fn render(
+    template: &CreateNotificationPayload,
+    vars: &BTreeMap<String, String>,
+) -> Result<CreateNotificationPayload, TemplateError> {
+    Ok(CreateNotificationPayload {
+        message: render_str(&template.message, vars)?,
+        format: template.format,
+        tags: template
+            .tags
+            .iter()
+            .map(|tag| render_str(tag, vars))
+            .collect::<Result<_, _>>()?,
+        priority: template.priority,
+        actions: template
+            .actions
+            .iter()
+            .map(|action| render_action(action, vars))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Renders the label and URLs of an action of a template.
+fn render_action(
+    action: &Action,
+    vars: &BTreeMap<String, String>,
+) -> Result<Action, TemplateError> {
+    let kind = match &action.kind {
+        ActionKind::Url { url } => ActionKind::Url {
+            url: render_str(url, vars)?,
+        },
+        ActionKind::Webhook { url, body } => ActionKind::Webhook {
+            url: render_str(url, vars)?,
+            body: body
+                .as_deref()
+                .map(|body| render_str(body, vars))
+                .transpose()?,
+        },
+    };
+
+    Ok(Action {
+        name: action.name.clone(),
+        label: render_str(&action.label, vars)?,
+        kind,
+        outcome: None,
+    })
+}
+
+/// Replaces the `{name}` placeholders in a string with variables.
+///
+/// Literal braces are written `{{` and `}}`.
+fn render_str(
+    template: &str,
+    vars: &BTreeMap<String, String>,
+) -> Result<String, TemplateError> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find(['{', '}']) {
+        let (before, after) = rest.split_at(start);
+        rendered.push_str(before);
+
+        if let Some(after) = after.strip_prefix("{{") {
+            rendered.push('{');
+            rest = after;
+        } else if let Some(after) = after.strip_prefix("}}") {
+            rendered.push('}');
+            rest = after;
+        } else if let Some(after) = after.strip_prefix('{') {
+            let Some((name, after)) = after.split_once('}') else {
+                return Err(TemplateError::UnclosedPlaceholder {
+                    template: template.to_owned(),
+                });
+            };
+
+            let value = vars.get(name).ok_or_else(|| {
+                TemplateError::MissingVariable {
+                    name: name.to_owned(),
+                    template: template.to_owned(),
+                }
+            })?;
+
+            rendered.push_str(value);
+            rest = after;
+        } else {
+            // NOTE: A single closing brace is kept as is.
+            rendered.push('}');
+            rest = after.strip_prefix('}').unwrap_or_default();
+        }
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Parses a `NAME=VALUE` variable.
+fn parse_var(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(name, value)| (name.to_owned(), value.to_owned()))
+        .ok_or_else(|| format!("invalid variable `{s}`, expected NAME=VALUE"))
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    fn vars(vars: &[(&str, &str)]) -> BTreeMap<String, String> {
+        vars.iter()
+            .map(|(name, value)| ((*name).to_owned(), (*value).to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn render_replaces_placeholders_in_message_tags_and_actions() {
+        let template = CreateNotificationPayload {
+            message: "The disk is full on {host} ({usage}%).".to_owned(),
+            format: Format::Markdown,
+            tags: vec!["disk".to_owned(), "{host}".to_owned()],
+            priority: Priority::High,
+            actions: vec![Action {
+                name: "dashboard".to_owned(),
+                label: "Open {host}".to_owned(),
+                kind: ActionKind::Url {
+                    url: "https://grafana/d/{host}".to_owned(),
+                },
+                outcome: None,
+            }],
+        };
+
+        let payload =
+            render(&template, &vars(&[("host", "db1"), ("usage", "98")]))
+                .unwrap();
+
+        assert_eq!(payload.message, "The disk is full on db1 (98%).");
+        assert_eq!(payload.tags, ["disk", "db1"]);
+        assert_eq!(payload.priority, Priority::High);
+        assert_eq!(payload.format, Format::Markdown);
+        assert_eq!(
+            payload.actions,
+            [Action {
+                name: "dashboard".to_owned(),
+                label: "Open db1".to_owned(),
+                kind: ActionKind::Url {
+                    url: "https://grafana/d/db1".to_owned(),
+                },
+                outcome: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn render_str_returns_an_error_on_missing_variable() {
+        let error = render_str("{host}", &vars(&[])).unwrap_err();
+        assert_eq!(error.to_string(), "missing variable `host` for `{host}`");
+    }
+
+    #[test]
+    fn render_str_returns_an_error_on_unclosed_placeholder() {
+        let error = render_str("{host", &vars(&[])).unwrap_err();
+        assert_eq!(error.to_string(), "unclosed placeholder in `{host`");
+    }
+
+    #[test]
+    fn render_str_unescapes_double_braces() {
+        assert_eq!(
+            render_str(
+                r#"{{"job":"{job}","labels":{{}}}}"#,
+                &vars(&[("job", "backup")])
+            )
+            .unwrap(),
+            r#"{"job":"backup","labels":{}}"#
+        );
+    }
 
-        Ok(())
+    #[test]
+    fn parse_var_splits_on_the_first_equal_sign() {
+        assert_eq!(
+            parse_var("query=a=b").unwrap(),
+            ("query".to_owned(), "a=b".to_owned())
+        );
     }
 }
@@ -3,30 +3,34 @@
 use clap::Parser;
 use eyre::Result;
 
-use ntf_api::ApiClient;
+use super::{Settings, targets::Targets};
 
 /// Arguments for `ntf-cli delete`.
 #[derive(Debug, Parser)]
 pub struct Delete {
-    /// ID of the notification to delete.
-    id: usize,
-    /// The API base URL.
-    #[arg(long = "url", default_value = "http://localhost:3000")]
-    base_url: String,
+    /// The notifications to delete.
+    #[command(flatten)]
+    targets: Targets,
 }
 
 impl super::Command for Delete {
     #[tracing::instrument(name = "delete", level = "trace", skip_all)]
-    async fn run(&self) -> Result<()> {
+    async fn run(&self, settings: &Settings) -> Result<()> {
         tracing::info!(params = ?self, "running delete");
 
-        let Self { id, base_url } = self;
-
-        let api = ApiClient::new(base_url);
-        let notification = api.delete_notification(*id).await?;
-
-        println!("deleted: {notification:?}");
-
-        Ok(())
+        let Self { targets } = self;
+
+        let api = settings.api_client();
+        targets
+            .run(
+                |id| api.delete_notification(id),
+                |notification| {
+                    settings.output.print(
+                        notification,
+                        format_args!("deleted: {notification:?}"),
+                    )
+                },
+            )
+            .await
     }
 }
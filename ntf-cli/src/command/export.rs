@@ -0,0 +1,147 @@
+//! The `export` subcommand.
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use ntf_api::{
+    ListQuery,
+    export::{ExportFormat, Exporter},
+};
+
+use super::Settings;
+
+/// The default number of notifications to fetch per request.
+const DEFAULT_PAGE_SIZE: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// Arguments for `ntf-cli export`.
+#[derive(Debug, Parser)]
+pub struct Export {
+    /// The format of the export [default: from the file extension, or json].
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+    /// The file to write to [default: the standard output].
+    #[arg(long, value_name = "PATH")]
+    file: Option<PathBuf>,
+    /// The number of notifications to fetch per request.
+    #[arg(long, default_value_t = DEFAULT_PAGE_SIZE)]
+    page_size: NonZeroUsize,
+}
+
+/// An export format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A JSON array.
+    Json,
+    /// One JSON object per line.
+    Ndjson,
+    /// Comma-separated values, with a header.
+    Csv,
+}
+
+impl super::Command for Export {
+    #[tracing::instrument(name = "export", level = "trace", skip_all)]
+    async fn run(&self, settings: &Settings) -> Result<()> {
+        tracing::info!(params = ?self, "running export");
+
+        let format = Format::resolve(self.format, self.file.as_deref());
+        let writer: Box<dyn Write> = match &self.file {
+            Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+            None => Box::new(io::stdout().lock()),
+        };
+
+        let api = settings.api_client();
+        let mut exporter = Exporter::new(format, writer);
+        let page_size = self.page_size.get();
+        let mut offset = 0;
+
+        loop {
+            let query = ListQuery {
+                offset,
+                limit: Some(page_size),
+            };
+
+            let page = api.list_notifications_page(query).await?;
+            tracing::debug!(offset, count = page.len(), "fetched a page");
+
+            for notification in &page {
+                exporter.write(notification)?;
+            }
+
+            offset += page.len();
+
+            if page.len() < page_size {
+                break;
+            }
+        }
+
+        exporter.finish()?;
+
+        if let Some(path) = &self.file {
+            println!("exported {offset} notifications to {}", path.display());
+        }
+
+        Ok(())
+    }
+}
+
+impl Format {
+    /// Resolves the export format, guessing it from the file extension if not
+    /// given explicitly.
+    pub fn resolve(format: Option<Self>, path: Option<&Path>) -> ExportFormat {
+        format.map_or_else(
+            || {
+                path.and_then(Path::extension)
+                    .and_then(|extension| extension.to_str())
+                    .and_then(ExportFormat::from_extension)
+                    .unwrap_or_default()
+            },
+            ExportFormat::from,
+        )
+    }
+}
+
+impl From<Format> for ExportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Json => Self::Json,
+            Format::Ndjson => Self::Ndjson,
+            Format::Csv => Self::Csv,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn resolve_prefers_the_explicit_format() {
+        let format =
+            Format::resolve(Some(Format::Csv), Some(Path::new("a.json")));
+        assert_eq!(format, ExportFormat::Csv);
+    }
+
+    #[test]
+    fn resolve_guesses_the_format_from_the_extension() {
+        let format = Format::resolve(None, Some(Path::new("backup.jsonl")));
+        assert_eq!(format, ExportFormat::Ndjson);
+    }
+
+    #[test]
+    fn resolve_defaults_to_json() {
+        assert_eq!(Format::resolve(None, None), ExportFormat::Json);
+        assert_eq!(
+            Format::resolve(None, Some(Path::new("backup.txt"))),
+            ExportFormat::Json
+        );
+    }
+}
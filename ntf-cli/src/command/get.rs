@@ -3,30 +3,33 @@
 use clap::Parser;
 use eyre::Result;
 
-use ntf_api::ApiClient;
+use super::{Settings, targets::Targets};
 
 /// Arguments for `ntf-cli get`.
 #[derive(Debug, Parser)]
 pub struct Get {
-    /// ID of the notification to get.
-    id: usize,
-    /// The API base URL.
-    #[arg(long = "url", default_value = "http://localhost:3000")]
-    base_url: String,
+    /// The notifications to get.
+    #[command(flatten)]
+    targets: Targets,
 }
 
 impl super::Command for Get {
     #[tracing::instrument(name = "get", level = "trace", skip_all)]
-    async fn run(&self) -> Result<()> {
+    async fn run(&self, settings: &Settings) -> Result<()> {
         tracing::info!(params = ?self, "running get");
 
-        let Self { id, base_url } = self;
-
-        let api = ApiClient::new(base_url);
-        let notification = api.get_notification(*id).await?;
-
-        println!("{notification:?}");
-
-        Ok(())
+        let Self { targets } = self;
+
+        let api = settings.api_client();
+        targets
+            .run(
+                |id| api.get_notification(id),
+                |notification| {
+                    settings
+                        .output
+                        .print(notification, format_args!("{notification:?}"))
+                },
+            )
+            .await
     }
 }
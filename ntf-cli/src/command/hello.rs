@@ -3,6 +3,8 @@
 use clap::Parser;
 use eyre::Result;
 
+use super::Settings;
+
 /// Arguments for `ntf-cli hello`.
 #[derive(Debug, Parser)]
 pub struct Hello {
@@ -12,7 +14,7 @@ pub struct Hello {
 
 impl super::Command for Hello {
     #[tracing::instrument(name = "hello", level = "trace", skip_all)]
-    async fn run(&self) -> Result<()> {
+    async fn run(&self, _settings: &Settings) -> Result<()> {
         tracing::info!(params = ?self, "running hello");
 
         let Self { name } = self;
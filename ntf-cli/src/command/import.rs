@@ -0,0 +1,49 @@
+//! The `import` subcommand.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use eyre::Result;
+
+use ntf_api::export;
+
+use super::{Settings, export::Format};
+
+/// Arguments for `ntf-cli import`.
+#[derive(Debug, Parser)]
+pub struct Import {
+    /// The file to import, or `-` for the standard input.
+    #[arg(value_name = "PATH", default_value = "-")]
+    file: PathBuf,
+    /// The format of the file [default: from the file extension, or json].
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+}
+
+impl super::Command for Import {
+    #[tracing::instrument(name = "import", level = "trace", skip_all)]
+    async fn run(&self, settings: &Settings) -> Result<()> {
+        tracing::info!(params = ?self, "running import");
+
+        let reader: Box<dyn Read> = if self.file == Path::new("-") {
+            Box::new(io::stdin().lock())
+        } else {
+            Box::new(BufReader::new(File::open(&self.file)?))
+        };
+
+        let format = Format::resolve(self.format, Some(&self.file));
+        let notifications = export::read(format, reader)?;
+
+        let api = settings.api_client();
+        let imported = api.import_notifications(&notifications).await?;
+
+        settings.output.print(
+            &imported,
+            format_args!("imported {} notifications", imported.len()),
+        )
+    }
+}
@@ -3,27 +3,39 @@
 use clap::Parser;
 use eyre::Result;
 
-use ntf_api::ApiClient;
+use super::Settings;
 
 /// Arguments for `ntf-cli list`.
 #[derive(Debug, Parser)]
 pub struct List {
-    /// The API base URL.
-    #[arg(long = "url", default_value = "http://localhost:3000")]
-    base_url: String,
+    /// Only list acknowledged notifications.
+    #[arg(long, conflicts_with = "unacked")]
+    acked: bool,
+    /// Only list unacknowledged notifications.
+    #[arg(long)]
+    unacked: bool,
 }
 
 impl super::Command for List {
     #[tracing::instrument(name = "list", level = "trace", skip_all)]
-    async fn run(&self) -> Result<()> {
+    async fn run(&self, settings: &Settings) -> Result<()> {
         tracing::info!(params = ?self, "running list");
 
-        let Self { base_url } = self;
-
-        let api = ApiClient::new(base_url);
-        let notifications = api.list_notifications().await?;
-
-        println!("notifications = {notifications:?}");
+        let Self { acked, unacked } = self;
+
+        let api = settings.api_client();
+        let notifications = api
+            .list_notifications()
+            .await?
+            .into_iter()
+            .filter(|notification| !acked || notification.ack)
+            .filter(|notification| !unacked || !notification.ack)
+            .collect::<Vec<_>>();
+
+        settings.output.print(
+            &notifications,
+            format_args!("notifications = {notifications:?}"),
+        )?;
 
         Ok(())
     }
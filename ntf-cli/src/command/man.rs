@@ -0,0 +1,41 @@
+//! The `man` subcommand.
+
+use std::{fs, io, path::PathBuf};
+
+use clap::{CommandFactory as _, Parser};
+use clap_mangen::Man as ManPage;
+use eyre::Result;
+
+use super::{NtfCli, Settings};
+
+/// Arguments for `ntf-cli man`.
+#[derive(Debug, Parser)]
+pub struct Man {
+    /// Write the pages for ntf-cli and all its subcommands to this directory.
+    ///
+    /// When omitted, only the main page is written to the standard output.
+    #[arg(long)]
+    dir: Option<PathBuf>,
+}
+
+impl super::Command for Man {
+    #[tracing::instrument(name = "man", level = "trace", skip_all)]
+    async fn run(&self, _settings: &Settings) -> Result<()> {
+        tracing::info!(params = ?self, "running man");
+
+        let Self { dir } = self;
+
+        let command = NtfCli::command();
+
+        match dir {
+            Some(dir) => {
+                fs::create_dir_all(dir)?;
+                clap_mangen::generate_to(command, dir)?;
+                println!("man pages written to {}", dir.display());
+            }
+            None => ManPage::new(command).render(&mut io::stdout())?,
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+//! Output formats for the results of commands.
+
+use std::fmt::Display;
+
+use clap::ValueEnum;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+
+use ntf_api::{Notification, NotificationEvent};
+
+/// The format to print the results in.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// JSON, one value per line.
+    Json,
+    /// Notification IDs, one per line.
+    Ids,
+}
+
+/// A value referring to notifications.
+pub trait NotificationIds {
+    /// Returns the IDs of the notifications.
+    fn ids(&self) -> Vec<usize>;
+}
+
+impl OutputFormat {
+    /// Prints `value` in the output format.
+    ///
+    /// In the text format, `text` is printed instead.
+    pub fn print<T: Serialize + NotificationIds>(
+        self,
+        value: &T,
+        text: impl Display,
+    ) -> Result<()> {
+        match self {
+            Self::Text => println!("{text}"),
+            // NOTE: Commands operating on several notifications print a value
+            // per notification, which is valid JSON Lines.
+            Self::Json => println!("{}", serde_json::to_string(value)?),
+            Self::Ids => {
+                for id in value.ids() {
+                    println!("{id}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl NotificationIds for Notification {
+    fn ids(&self) -> Vec<usize> {
+        vec![self.id]
+    }
+}
+
+impl NotificationIds for Vec<Notification> {
+    fn ids(&self) -> Vec<usize> {
+        self.iter().map(|notification| notification.id).collect()
+    }
+}
+
+impl NotificationIds for NotificationEvent {
+    fn ids(&self) -> Vec<usize> {
+        self.notification().ids()
+    }
+}
@@ -0,0 +1,247 @@
+//! Selection of the notifications to operate on.
+
+use std::{
+    collections::BTreeSet,
+    io::{self, Read as _},
+    num::NonZeroUsize,
+    str::FromStr,
+};
+
+use clap::Args;
+use clap_complete::ArgValueCompleter;
+use eyre::{Report, Result, eyre};
+use futures::{StreamExt as _, stream};
+use thiserror::Error;
+
+use ntf_api::Notification;
+
+use crate::error;
+
+use super::completions::complete_ids;
+
+/// The maximum number of IDs in a range.
+const MAX_RANGE_LENGTH: usize = 10_000;
+
+/// The notifications to operate on.
+#[derive(Debug, Args)]
+pub struct Targets {
+    /// IDs of the notifications, as `ID`, `FIRST-LAST` or `-` to read from
+    /// the standard input.
+    #[arg(
+        value_name = "ID",
+        required = true,
+        add = ArgValueCompleter::new(complete_ids),
+    )]
+    ids: Vec<IdArg>,
+    /// The maximum number of concurrent requests.
+    #[arg(short, long, default_value = "8")]
+    jobs: NonZeroUsize,
+}
+
+/// A notification ID argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdArg {
+    /// A single ID.
+    Id(usize),
+    /// An inclusive range of IDs.
+    Range(usize, usize),
+    /// IDs to read from the standard input.
+    Stdin,
+}
+
+/// Some operations have failed.
+#[derive(Debug, Error)]
+#[error("{failed} of {total} operations have failed")]
+pub struct BatchError {
+    /// The number of failed operations.
+    pub failed: usize,
+    /// The total number of operations.
+    pub total: usize,
+}
+
+impl Targets {
+    /// Runs `operation` concurrently on each notification.
+    ///
+    /// Successfully processed notifications are passed to `report` in order,
+    /// while failures are printed as they come. When operating on a single
+    /// notification, its error is returned as is.
+    pub async fn run<Op, Fut, E>(
+        &self,
+        operation: Op,
+        report: impl Fn(&Notification) -> Result<()>,
+    ) -> Result<()>
+    where
+        Op: Fn(usize) -> Fut,
+        Fut: Future<Output = Result<Notification, E>>,
+        E: Into<Report>,
+    {
+        let ids = self.expand()?;
+
+        if let [id] = ids.as_slice() {
+            return report(&operation(*id).await.map_err(Into::into)?);
+        }
+
+        let mut results = stream::iter(ids.iter().copied())
+            .map(|id| {
+                let result = operation(id);
+                async move { (id, result.await) }
+            })
+            .buffered(self.jobs.get());
+
+        let mut failed = 0;
+
+        while let Some((id, result)) = results.next().await {
+            match result {
+                Ok(notification) => report(&notification)?,
+                Err(error) => {
+                    let error = error.into();
+                    tracing::warn!(id, ?error, "operation failed");
+                    error!("#{id}: {error}");
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed == 0 {
+            Ok(())
+        } else {
+            Err(BatchError {
+                failed,
+                total: ids.len(),
+            }
+            .into())
+        }
+    }
+
+    /// Expands the ID arguments into a list of unique IDs.
+    fn expand(&self) -> Result<Vec<usize>> {
+        let mut args = self.ids.clone();
+
+        if args.iter().filter(|arg| **arg == IdArg::Stdin).count() > 1 {
+            return Err(eyre!("`-` can only be given once"));
+        }
+
+        if let Some(index) = args.iter().position(|arg| *arg == IdArg::Stdin) {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            args.splice(index..=index, parse_ids(&input)?);
+        }
+
+        Ok(expand(&args))
+    }
+}
+
+impl FromStr for IdArg {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "-" {
+            return Ok(Self::Stdin);
+        }
+
+        match s.split_once('-') {
+            Some((first, last)) => {
+                let (first, last) = (first.parse()?, last.parse()?);
+
+                if first > last {
+                    return Err(eyre!("invalid range {s}"));
+                }
+
+                if last - first >= MAX_RANGE_LENGTH {
+                    return Err(eyre!(
+                        "range {s} is too large (at most {MAX_RANGE_LENGTH} IDs)"
+                    ));
+                }
+
+                Ok(Self::Range(first, last))
+            }
+            None => Ok(Self::Id(s.parse()?)),
+        }
+    }
+}
+
+/// Parses whitespace-separated IDs and ranges.
+fn parse_ids(input: &str) -> Result<Vec<IdArg>> {
+    input
+        .split_whitespace()
+        .map(|word| match word.parse()? {
+            IdArg::Stdin => Err(eyre!("invalid ID {word}")),
+            arg => Ok(arg),
+        })
+        .collect()
+}
+
+/// Expands the ID arguments into a list of unique IDs, in order.
+fn expand(args: &[IdArg]) -> Vec<usize> {
+    let mut seen = BTreeSet::new();
+
+    args.iter()
+        .filter_map(|arg| match *arg {
+            IdArg::Id(id) => Some(id..=id),
+            IdArg::Range(first, last) => Some(first..=last),
+            IdArg::Stdin => None,
+        })
+        .flatten()
+        .filter(|id| seen.insert(*id))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn id_arg_parses_ids_ranges_and_stdin() {
+        assert_eq!("3".parse::<IdArg>().unwrap(), IdArg::Id(3));
+        assert_eq!("7-12".parse::<IdArg>().unwrap(), IdArg::Range(7, 12));
+        assert_eq!("-".parse::<IdArg>().unwrap(), IdArg::Stdin);
+    }
+
+    #[test]
+    fn id_arg_rejects_invalid_values() {
+        let error = |s: &str| s.parse::<IdArg>().unwrap_err().to_string();
+
+        assert_eq!(error("abc"), "invalid digit found in string");
+        assert_eq!(error("12-7"), "invalid range 12-7");
+        assert_eq!(error("1-"), "cannot parse integer from empty string");
+        assert_eq!(
+            error("1-18446744073709551615"),
+            "range 1-18446744073709551615 is too large (at most 10000 IDs)"
+        );
+        assert_eq!(error("1-2-3"), "invalid digit found in string");
+    }
+
+    #[test]
+    fn expand_rejects_repeated_stdin() {
+        let targets = Targets {
+            ids: vec![IdArg::Stdin, IdArg::Id(1), IdArg::Stdin],
+            jobs: NonZeroUsize::MIN,
+        };
+        assert_eq!(
+            targets.expand().unwrap_err().to_string(),
+            "`-` can only be given once"
+        );
+    }
+
+    #[test]
+    fn parse_ids_splits_on_whitespace() {
+        assert_eq!(
+            parse_ids("1\n2 4-5\n").unwrap(),
+            [IdArg::Id(1), IdArg::Id(2), IdArg::Range(4, 5)]
+        );
+    }
+
+    #[test]
+    fn parse_ids_rejects_stdin() {
+        let error = parse_ids("1 -").unwrap_err();
+        assert_eq!(error.to_string(), "invalid ID -");
+    }
+
+    #[test]
+    fn expand_removes_duplicates_and_keeps_order() {
+        let args = [IdArg::Id(3), IdArg::Id(5), IdArg::Range(4, 6)];
+        assert_eq!(expand(&args), [3, 5, 4, 6]);
+    }
+}
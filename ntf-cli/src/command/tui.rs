@@ -0,0 +1,109 @@
+//! The `tui` subcommand.
+
+mod app;
+mod markdown;
+mod ui;
+
+use std::time::{Duration, Instant};
+
+use clap::Parser;
+use eyre::Result;
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyEventKind},
+};
+
+use ntf_api::ApiClient;
+
+use self::app::{Action, App};
+
+use super::Settings;
+
+/// The maximum time to wait for a key press before refreshing the screen.
+const TICK: Duration = Duration::from_millis(250);
+
+/// Arguments for `ntf-cli tui`.
+#[derive(Debug, Parser)]
+pub struct Tui {
+    /// The refresh interval in seconds.
+    #[arg(long, default_value_t = 2)]
+    interval: u64,
+}
+
+impl super::Command for Tui {
+    #[tracing::instrument(name = "tui", level = "trace", skip_all)]
+    async fn run(&self, settings: &Settings) -> Result<()> {
+        tracing::info!(params = ?self, "running tui");
+
+        let api = settings.api_client();
+
+        // NOTE: Fetch the notifications before entering the alternate screen so
+        // that connection errors are reported like in any other command.
+        let mut app = App::default();
+        app.set_notifications(api.list_notifications().await?);
+
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal, &api, &mut app).await;
+        ratatui::restore();
+
+        result
+    }
+}
+
+impl Tui {
+    /// Runs the event loop until the user quits.
+    async fn event_loop(
+        &self,
+        terminal: &mut DefaultTerminal,
+        api: &ApiClient,
+        app: &mut App,
+    ) -> Result<()> {
+        let interval = Duration::from_secs(self.interval);
+        let mut last_refresh = Instant::now();
+
+        loop {
+            terminal.draw(|frame| ui::draw(frame, app))?;
+
+            let action = if tokio::task::block_in_place(|| event::poll(TICK))? {
+                match event::read()? {
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
+                        app.handle_key(key)
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            };
+
+            match action {
+                Some(Action::Quit) => return Ok(()),
+                Some(Action::Ack(id)) => {
+                    if let Err(error) = api.ack_notification(id).await {
+                        app.set_status(format!("Failed to ack #{id}: {error}"));
+                    }
+                }
+                Some(Action::Delete(id)) => {
+                    if let Err(error) = api.delete_notification(id).await {
+                        app.set_status(format!(
+                            "Failed to delete #{id}: {error}"
+                        ));
+                    }
+                }
+                Some(Action::Refresh) | None => {}
+            }
+
+            if action.is_some() || last_refresh.elapsed() >= interval {
+                refresh(api, app).await;
+                last_refresh = Instant::now();
+            }
+        }
+    }
+}
+
+/// Refreshes the notifications.
+async fn refresh(api: &ApiClient, app: &mut App) {
+    match api.list_notifications().await {
+        Ok(notifications) => app.set_notifications(notifications),
+        Err(error) => app.set_status(format!("Failed to refresh: {error}")),
+    }
+}
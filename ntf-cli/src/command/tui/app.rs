@@ -0,0 +1,340 @@
+//! The state of the TUI.
+
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyModifiers},
+    widgets::ListState,
+};
+
+use ntf_api::Notification;
+
+/// The state of the TUI.
+#[derive(Debug, Default)]
+pub struct App {
+    /// The notifications fetched from the server.
+    notifications: Vec<Notification>,
+    /// The acknowledgement state to show.
+    filter: Filter,
+    /// The search query.
+    search: String,
+    /// The input mode.
+    mode: Mode,
+    /// The state of the list widget.
+    list_state: ListState,
+    /// A message to show in the status bar.
+    status: Option<String>,
+}
+
+/// The acknowledgement state of the notifications to show.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// Show the unacknowledged notifications.
+    #[default]
+    Unacked,
+    /// Show the acknowledged notifications.
+    Acked,
+    /// Show all the notifications.
+    All,
+}
+
+/// The input mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Keys trigger actions.
+    #[default]
+    Normal,
+    /// Keys edit the search query.
+    Search,
+}
+
+/// An action to perform following a key press.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Quit the TUI.
+    Quit,
+    /// Refresh the notifications.
+    Refresh,
+    /// Acknowledge the notification with the given ID.
+    Ack(usize),
+    /// Delete the notification with the given ID.
+    Delete(usize),
+}
+
+impl App {
+    /// Replaces the notifications, keeping the selection if possible.
+    pub fn set_notifications(&mut self, notifications: Vec<Notification>) {
+        let selected_id = self.selected().map(|notification| notification.id);
+        self.notifications = notifications;
+        self.reselect(selected_id);
+    }
+
+    /// Returns the notifications matching the filter and search query.
+    pub fn visible(&self) -> Vec<&Notification> {
+        let search = self.search.to_lowercase();
+
+        self.notifications
+            .iter()
+            .filter(|notification| match self.filter {
+                Filter::Unacked => !notification.ack,
+                Filter::Acked => notification.ack,
+                Filter::All => true,
+            })
+            .filter(|notification| {
+                search.is_empty()
+                    || notification.message.to_lowercase().contains(&search)
+                    || notification
+                        .tags
+                        .iter()
+                        .any(|tag| tag.to_lowercase().contains(&search))
+            })
+            .collect()
+    }
+
+    /// Returns the selected notification.
+    pub fn selected(&self) -> Option<&Notification> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.visible().get(index).copied())
+    }
+
+    /// Returns the acknowledgement filter.
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Returns the search query.
+    pub fn search(&self) -> &str {
+        &self.search
+    }
+
+    /// Returns the input mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Returns the state of the list widget.
+    pub fn list_state_mut(&mut self) -> &mut ListState {
+        &mut self.list_state
+    }
+
+    /// Returns the status message.
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Sets the status message.
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = Some(status.into());
+    }
+
+    /// Handles a key press, returning the action to perform if any.
+    pub fn handle_key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL)
+            && key.code == KeyCode::Char('c')
+        {
+            return Some(Action::Quit);
+        }
+
+        match self.mode {
+            Mode::Normal => self.handle_normal_key(key.code),
+            Mode::Search => {
+                self.handle_search_key(key.code);
+                None
+            }
+        }
+    }
+
+    /// Handles a key press in normal mode.
+    fn handle_normal_key(&mut self, code: KeyCode) -> Option<Action> {
+        self.status = None;
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            KeyCode::Char('r') => return Some(Action::Refresh),
+            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
+            KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
+            KeyCode::Char('a') => {
+                return self.selected().map(|ntf| Action::Ack(ntf.id));
+            }
+            KeyCode::Char('d') => {
+                return self.selected().map(|ntf| Action::Delete(ntf.id));
+            }
+            KeyCode::Char('/') => self.mode = Mode::Search,
+            KeyCode::Tab => self.toggle_filter(),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Handles a key press in search mode.
+    fn handle_search_key(&mut self, code: KeyCode) {
+        let selected_id = self.selected().map(|notification| notification.id);
+
+        match code {
+            KeyCode::Enter => self.mode = Mode::Normal,
+            KeyCode::Esc => {
+                self.search.clear();
+                self.mode = Mode::Normal;
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) => self.search.push(c),
+            _ => {}
+        }
+
+        self.reselect(selected_id);
+    }
+
+    /// Cycles through the acknowledgement filters.
+    fn toggle_filter(&mut self) {
+        let selected_id = self.selected().map(|notification| notification.id);
+
+        self.filter = match self.filter {
+            Filter::Unacked => Filter::Acked,
+            Filter::Acked => Filter::All,
+            Filter::All => Filter::Unacked,
+        };
+
+        self.reselect(selected_id);
+    }
+
+    /// Selects the next notification.
+    fn select_next(&mut self) {
+        let count = self.visible().len();
+        let next = self
+            .list_state
+            .selected()
+            .map_or(0, |index| (index + 1).min(count.saturating_sub(1)));
+
+        if count > 0 {
+            self.list_state.select(Some(next));
+        }
+    }
+
+    /// Selects the previous notification.
+    fn select_previous(&mut self) {
+        let previous = self
+            .list_state
+            .selected()
+            .map_or(0, |index| index.saturating_sub(1));
+
+        if !self.visible().is_empty() {
+            self.list_state.select(Some(previous));
+        }
+    }
+
+    /// Selects the notification with the given ID if visible.
+    ///
+    /// Otherwise, keeps the current index in the bounds of the visible list.
+    fn reselect(&mut self, id: Option<usize>) {
+        let visible = self.visible();
+        let index = id
+            .and_then(|id| visible.iter().position(|ntf| ntf.id == id))
+            .or_else(|| {
+                let last = visible.len().checked_sub(1)?;
+                Some(self.list_state.selected().unwrap_or(0).min(last))
+            });
+
+        self.list_state.select(index);
+    }
+}
+
+impl Filter {
+    /// Returns the label of the filter.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Unacked => "Unacked",
+            Self::Acked => "Acked",
+            Self::All => "All",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    fn notification(id: usize, message: &str, ack: bool) -> Notification {
+        Notification {
+            ack,
+            ..Notification::new(id, message)
+        }
+    }
+
+    fn app() -> App {
+        let mut app = App::default();
+        app.set_notifications(vec![
+            notification(1, "disk full", false),
+            notification(2, "backup done", true),
+            notification(3, "disk almost full", false),
+        ]);
+        app
+    }
+
+    fn press(app: &mut App, code: KeyCode) -> Option<Action> {
+        app.handle_key(KeyEvent::from(code))
+    }
+
+    fn visible_ids(app: &App) -> Vec<usize> {
+        app.visible().iter().map(|ntf| ntf.id).collect()
+    }
+
+    #[test]
+    fn shows_unacked_notifications_by_default() {
+        assert_eq!(visible_ids(&app()), [1, 3]);
+    }
+
+    #[test]
+    fn tab_cycles_through_filters() {
+        let mut app = app();
+
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(visible_ids(&app), [2]);
+
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(visible_ids(&app), [1, 2, 3]);
+    }
+
+    #[test]
+    fn search_filters_by_message() {
+        let mut app = app();
+
+        press(&mut app, KeyCode::Char('/'));
+        for c in "almost".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+
+        assert_eq!(app.mode(), Mode::Normal);
+        assert_eq!(visible_ids(&app), [3]);
+    }
+
+    #[test]
+    fn ack_and_delete_apply_to_the_selected_notification() {
+        let mut app = app();
+
+        press(&mut app, KeyCode::Char('j'));
+
+        assert_eq!(press(&mut app, KeyCode::Char('a')), Some(Action::Ack(3)));
+        assert_eq!(
+            press(&mut app, KeyCode::Char('d')),
+            Some(Action::Delete(3))
+        );
+    }
+
+    #[test]
+    fn set_notifications_keeps_the_selected_notification() {
+        let mut app = app();
+        press(&mut app, KeyCode::Char('j'));
+
+        app.set_notifications(vec![
+            notification(3, "disk almost full", false),
+            notification(4, "new", false),
+        ]);
+
+        assert_eq!(app.selected().map(|ntf| ntf.id), Some(3));
+    }
+}
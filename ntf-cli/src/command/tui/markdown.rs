@@ -0,0 +1,283 @@
+//! The rendering of Markdown messages in the terminal.
+
+use std::mem;
+
+use pulldown_cmark::{
+    Event, HeadingLevel, LinkType, Options, Parser, Tag, TagEnd,
+};
+use ratatui::{
+    style::{Color, Style, Stylize as _},
+    text::{Line, Span},
+};
+
+/// Renders a Markdown message to styled lines.
+///
+/// Raw HTML is shown as is, and control characters are removed so that the
+/// message cannot send escape sequences to the terminal.
+pub fn render(message: &str) -> Vec<Line<'static>> {
+    let mut renderer = Renderer::default();
+
+    for event in Parser::new_ext(message, Options::ENABLE_STRIKETHROUGH) {
+        renderer.push(event);
+    }
+
+    renderer.finish()
+}
+
+/// The state of the rendering.
+#[derive(Debug, Default)]
+struct Renderer {
+    /// The lines rendered so far.
+    lines: Vec<Line<'static>>,
+    /// The spans of the current line.
+    spans: Vec<Span<'static>>,
+    /// The styles of the enclosing inline elements.
+    styles: Vec<Style>,
+    /// The next number of the enclosing lists, `None` for bullet lists.
+    lists: Vec<Option<u64>>,
+    /// The destinations of the enclosing links, if to be shown.
+    links: Vec<Option<String>>,
+    /// The depth of the enclosing block quotes.
+    quotes: usize,
+    /// Whether the text is in a code block.
+    code_block: bool,
+}
+
+impl Renderer {
+    /// Renders an event.
+    fn push(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.code_block => {
+                for line in text.lines() {
+                    self.span(&format!("  {line}"), code_style());
+                    self.new_line();
+                }
+            }
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                self.span(&text, self.style());
+            }
+            Event::Code(code) => self.span(&code, code_style()),
+            Event::SoftBreak => self.span(" ", self.style()),
+            Event::HardBreak => self.new_line(),
+            Event::Rule => {
+                self.block_start();
+                self.span("───", Style::new().dim());
+                self.new_line();
+            }
+            Event::TaskListMarker(checked) => {
+                let marker = if checked { "[x] " } else { "[ ] " };
+                self.span(marker, self.style());
+            }
+            _ => {}
+        }
+    }
+
+    /// Renders the start of an element.
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Paragraph => self.block_start(),
+            Tag::Heading { level, .. } => {
+                self.block_start();
+                let style = if level == HeadingLevel::H1 {
+                    Style::new().bold().underlined()
+                } else {
+                    Style::new().bold()
+                };
+                self.styles.push(style);
+            }
+            Tag::BlockQuote(_) => {
+                self.block_start();
+                self.quotes += 1;
+            }
+            Tag::CodeBlock(_) => {
+                self.block_start();
+                self.code_block = true;
+            }
+            Tag::List(start) => {
+                if self.lists.is_empty() {
+                    self.block_start();
+                } else {
+                    self.new_line();
+                }
+                self.lists.push(start);
+            }
+            Tag::Item => self.item_start(),
+            Tag::Emphasis => self.styles.push(Style::new().italic()),
+            Tag::Strong => self.styles.push(Style::new().bold()),
+            Tag::Strikethrough => self.styles.push(Style::new().crossed_out()),
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            }
+            | Tag::Image {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                let shown =
+                    !matches!(link_type, LinkType::Autolink | LinkType::Email);
+                self.links.push(shown.then(|| dest_url.into_string()));
+                self.styles.push(Style::new().fg(Color::Blue).underlined());
+            }
+            _ => {}
+        }
+    }
+
+    /// Renders the end of an element.
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph | TagEnd::Item => self.new_line(),
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.new_line();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.new_line();
+                self.quotes = self.quotes.saturating_sub(1);
+            }
+            TagEnd::CodeBlock => self.code_block = false,
+            TagEnd::List(_) => {
+                self.new_line();
+                self.lists.pop();
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link | TagEnd::Image => {
+                self.styles.pop();
+                if let Some(Some(dest_url)) = self.links.pop() {
+                    self.span(&format!(" <{dest_url}>"), Style::new().dim());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Starts a list item with its bullet or number.
+    fn item_start(&mut self) {
+        if !self.spans.is_empty() {
+            self.new_line();
+        }
+
+        let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+        let marker = match self.lists.last_mut() {
+            Some(Some(number)) => {
+                let marker = format!("{indent}{number}. ");
+                *number += 1;
+                marker
+            }
+            _ => format!("{indent}• "),
+        };
+
+        self.span(&marker, Style::new());
+    }
+
+    /// Starts a block, separated from the previous one by a blank line.
+    fn block_start(&mut self) {
+        if !self.spans.is_empty() {
+            self.new_line();
+        }
+
+        // NOTE: The paragraphs of list items are kept together.
+        let separated =
+            self.lines.last().is_none_or(|line| line.spans.is_empty());
+        if !separated && self.lists.is_empty() {
+            self.lines.push(Line::default());
+        }
+    }
+
+    /// Adds a span to the current line, prefixed by the block quote markers.
+    fn span(&mut self, content: &str, style: Style) {
+        if self.spans.is_empty() && self.quotes > 0 {
+            self.spans.push(Span::styled(
+                "│ ".repeat(self.quotes),
+                Style::new().dim(),
+            ));
+        }
+
+        let content = content
+            .chars()
+            .filter(|character| !character.is_control())
+            .collect::<String>();
+        self.spans.push(Span::styled(content, style));
+    }
+
+    /// Ends the current line, if not empty.
+    fn new_line(&mut self) {
+        if !self.spans.is_empty() {
+            self.lines.push(Line::from(mem::take(&mut self.spans)));
+        }
+    }
+
+    /// Returns the style of the enclosing inline elements.
+    fn style(&self) -> Style {
+        self.styles
+            .iter()
+            .fold(Style::new(), |style, inner| style.patch(*inner))
+    }
+
+    /// Returns the rendered lines.
+    fn finish(mut self) -> Vec<Line<'static>> {
+        self.new_line();
+        self.lines
+    }
+}
+
+/// Returns the style of code.
+fn code_style() -> Style {
+    Style::new().fg(Color::Yellow)
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    fn plain(lines: &[Line<'_>]) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn render_separates_blocks_and_marks_lists() {
+        let lines = render(
+            "# Disk full\n\nOn *db1*:\n\n- `/var`\n- `/home`\n\n1. a\n2. b",
+        );
+
+        assert_eq!(
+            plain(&lines),
+            [
+                "Disk full",
+                "",
+                "On db1:",
+                "",
+                "• /var",
+                "• /home",
+                "",
+                "1. a",
+                "2. b"
+            ]
+        );
+    }
+
+    #[test]
+    fn render_shows_link_destinations() {
+        let lines =
+            render("See [the docs](https://example.com) or <https://a.b>.");
+
+        assert_eq!(
+            plain(&lines),
+            ["See the docs <https://example.com> or https://a.b."]
+        );
+    }
+
+    #[test]
+    fn render_removes_control_characters() {
+        let lines = render("Hello \u{1b}[31mworld");
+
+        assert_eq!(plain(&lines), ["Hello [31mworld"]);
+    }
+}
@@ -0,0 +1,139 @@
+//! The rendering of the TUI.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize as _},
+    text::{Line, Span, Text},
+    widgets::{Block, List, ListItem, Paragraph, Tabs, Wrap},
+};
+
+use ntf_api::{Format, Notification};
+
+use super::{
+    app::{App, Filter, Mode},
+    markdown,
+};
+
+/// The key bindings shown in the footer.
+const HELP: &str = "j/k: move  a: ack  d: delete  /: search  tab: filter  \
+    r: refresh  q: quit";
+
+/// Draws the TUI.
+pub fn draw(frame: &mut Frame<'_>, app: &mut App) {
+    let [header, main, footer] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [list, details] =
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Fill(1)])
+            .areas(main);
+
+    draw_header(frame, app, header);
+    draw_list(frame, app, list);
+    draw_details(frame, app, details);
+    draw_footer(frame, app, footer);
+}
+
+/// Draws the filter tabs and the search query.
+fn draw_header(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let [tabs, search] =
+        Layout::horizontal([Constraint::Length(30), Constraint::Fill(1)])
+            .areas(area);
+
+    let filters = [Filter::Unacked, Filter::Acked, Filter::All];
+    let selected = filters.iter().position(|filter| *filter == app.filter());
+
+    frame.render_widget(
+        Tabs::new(filters.map(Filter::label))
+            .select(selected)
+            .highlight_style(Style::new().bold().reversed()),
+        tabs,
+    );
+
+    let cursor = if app.mode() == Mode::Search {
+        "█"
+    } else {
+        ""
+    };
+    let search_line = Line::from(vec![
+        Span::raw("/").dim(),
+        Span::raw(app.search()),
+        Span::raw(cursor),
+    ]);
+
+    frame.render_widget(Paragraph::new(search_line), search);
+}
+
+/// Draws the list of notifications.
+fn draw_list(frame: &mut Frame<'_>, app: &mut App, area: Rect) {
+    let items = app
+        .visible()
+        .into_iter()
+        .map(|notification| {
+            let ack = if notification.ack { "✓" } else { " " };
+            ListItem::new(format!(
+                "{ack} #{:<4} {}",
+                notification.id, notification.message
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    let list = List::new(items)
+        .block(Block::bordered().title(" Notifications "))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(list, area, app.list_state_mut());
+}
+
+/// Draws the details of the selected notification.
+fn draw_details(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Details ");
+
+    let text = match app.selected() {
+        Some(notification) => [
+            Line::from(format!("#{}", notification.id).bold()),
+            Line::default(),
+        ]
+        .into_iter()
+        .chain(message(notification))
+        .chain([
+            Line::default(),
+            Line::from(format!(
+                "Acknowledged: {}",
+                if notification.ack { "yes" } else { "no" }
+            )),
+            Line::from(format!("Priority: {}", notification.priority)),
+            Line::from(format!("Tags: {}", notification.tags.join(", "))),
+        ])
+        .collect(),
+        None => vec![Line::from("No notification selected.").dim()],
+    };
+
+    frame.render_widget(
+        Paragraph::new(text).block(block).wrap(Wrap { trim: false }),
+        area,
+    );
+}
+
+/// Returns the lines of the message of a notification, rendered according to
+/// its format.
+fn message(notification: &Notification) -> Vec<Line<'static>> {
+    match notification.format {
+        Format::Plain => Text::from(notification.message.clone()).lines,
+        Format::Markdown => markdown::render(&notification.message),
+    }
+}
+
+/// Draws the status message or the key bindings.
+fn draw_footer(frame: &mut Frame<'_>, app: &App, area: Rect) {
+    let footer = match app.status() {
+        Some(status) => Line::from(status).red(),
+        None => Line::from(HELP).dim(),
+    };
+
+    frame.render_widget(Paragraph::new(footer), area);
+}
@@ -0,0 +1,455 @@
+//! The `watch` subcommand.
+
+use std::{collections::BTreeMap, num::NonZeroU64, time::Duration};
+
+use clap::Parser;
+use eyre::Result;
+
+use ntf_api::{
+    ApiClient, ApiError, ListError, Notification, NotificationEvent, WatchError,
+};
+
+use crate::warning;
+
+use super::Settings;
+
+/// The default polling interval, in seconds.
+const DEFAULT_INTERVAL: NonZeroU64 = NonZeroU64::new(5).unwrap();
+/// The delay before the first reconnection attempt.
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The maximum delay between reconnection attempts.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Arguments for `ntf-cli watch`.
+#[derive(Debug, Parser)]
+pub struct Watch {
+    /// Only show notifications with this tag (can be repeated).
+    #[arg(short, long = "tag")]
+    tags: Vec<String>,
+    /// Only show acknowledged notifications.
+    #[arg(long, conflicts_with = "unacked")]
+    acked: bool,
+    /// Only show unacknowledged notifications.
+    #[arg(long)]
+    unacked: bool,
+    /// A shell command to run on each event.
+    ///
+    /// The event is described by the `NTF_EVENT`, `NTF_ID`, `NTF_MESSAGE`,
+    /// `NTF_ACK`, `NTF_TAGS` and `NTF_PRIORITY` environment variables.
+    #[arg(long, value_name = "COMMAND")]
+    exec: Option<String>,
+    /// The polling interval in seconds, when the server cannot push events.
+    #[arg(long, default_value_t = DEFAULT_INTERVAL)]
+    interval: NonZeroU64,
+    /// Poll the server even if it can push events.
+    #[arg(long)]
+    poll: bool,
+}
+
+/// The state of a running watch.
+struct Watcher<'a> {
+    /// The arguments of the command.
+    args: &'a Watch,
+    /// The settings.
+    settings: &'a Settings,
+    /// The API client.
+    api: ApiClient,
+    /// The last known notifications, once synchronised with the server.
+    known: Option<BTreeMap<usize, Notification>>,
+    /// The delay before the next reconnection attempt.
+    retry_delay: Duration,
+}
+
+/// Why the watcher has been disconnected from the server.
+enum Disconnection {
+    /// The server cannot push events.
+    Unsupported,
+    /// The server has closed the connection.
+    Closed,
+    /// An error has occurred while communicating with the server.
+    Error(ApiError),
+}
+
+impl super::Command for Watch {
+    #[tracing::instrument(name = "watch", level = "trace", skip_all)]
+    async fn run(&self, settings: &Settings) -> Result<()> {
+        tracing::info!(params = ?self, "running watch");
+
+        let mut watcher = Watcher {
+            args: self,
+            settings,
+            api: settings.api_client(),
+            known: None,
+            retry_delay: MIN_RETRY_DELAY,
+        };
+
+        watcher.run().await
+    }
+}
+
+impl Watcher<'_> {
+    /// Watches the notifications, reconnecting on errors.
+    async fn run(&mut self) -> Result<()> {
+        let mut push = !self.args.poll;
+
+        loop {
+            let disconnection = if push {
+                self.watch_events().await?
+            } else {
+                self.poll().await?
+            };
+
+            match disconnection {
+                Disconnection::Unsupported => {
+                    tracing::info!("server push unsupported, polling instead");
+                    push = false;
+                }
+                // NOTE: Retrying would be refused the same way.
+                Disconnection::Error(ApiError::Unauthorised) => {
+                    return Err(ApiError::Unauthorised.into());
+                }
+                Disconnection::Closed | Disconnection::Error(_) => {
+                    disconnection.report();
+                    self.wait_before_reconnecting().await;
+                }
+            }
+        }
+    }
+
+    /// Waits before reconnecting, increasing the delay for the next time.
+    async fn wait_before_reconnecting(&mut self) {
+        let delay = self.retry_delay;
+        warning!("Reconnecting in {}s…", delay.as_secs());
+        tokio::time::sleep(delay).await;
+        self.retry_delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+
+    /// Watches the events pushed by the server.
+    async fn watch_events(&mut self) -> Result<Disconnection> {
+        let mut stream = match self.api.watch_notifications().await {
+            Ok(stream) => stream,
+            Err(WatchError::Unsupported) => {
+                return Ok(Disconnection::Unsupported);
+            }
+            Err(WatchError::ApiError(error)) => {
+                return Ok(Disconnection::Error(error));
+            }
+        };
+
+        // NOTE: Synchronise after subscribing so that events occurring while
+        // disconnected are not missed. The events received in between are
+        // already in the synchronised notifications, and are skipped.
+        if let Some(disconnection) = self.sync().await? {
+            return Ok(disconnection);
+        }
+
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(event) => {
+                    if self.apply(&event) {
+                        self.handle(&event).await?;
+                    }
+                }
+                Err(error) => return Ok(Disconnection::Error(error)),
+            }
+        }
+
+        Ok(Disconnection::Closed)
+    }
+
+    /// Polls the server periodically.
+    async fn poll(&mut self) -> Result<Disconnection> {
+        let interval = Duration::from_secs(self.args.interval.get());
+
+        loop {
+            if let Some(disconnection) = self.sync().await? {
+                return Ok(disconnection);
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Synchronises the known notifications with the server.
+    ///
+    /// Handles the events that occurred since the last synchronisation, and
+    /// returns the disconnection reason if the server cannot be reached.
+    async fn sync(&mut self) -> Result<Option<Disconnection>> {
+        let notifications = match self.api.list_notifications().await {
+            Ok(notifications) => notifications,
+            Err(ListError::ApiError(error)) => {
+                return Ok(Some(Disconnection::Error(error)));
+            }
+        };
+
+        self.retry_delay = MIN_RETRY_DELAY;
+
+        let events = self
+            .known
+            .as_ref()
+            .map(|known| diff(known, &notifications))
+            .unwrap_or_default();
+
+        self.known = Some(
+            notifications
+                .into_iter()
+                .map(|notification| (notification.id, notification))
+                .collect(),
+        );
+
+        for event in &events {
+            self.handle(event).await?;
+        }
+
+        Ok(None)
+    }
+
+    /// Applies an event to the known notifications.
+    ///
+    /// Returns whether the event is new, i.e. not already reflected in the
+    /// known notifications.
+    fn apply(&mut self, event: &NotificationEvent) -> bool {
+        self.known.as_mut().is_none_or(|known| apply(known, event))
+    }
+
+    /// Prints an event and runs the hook if it matches the filters.
+    async fn handle(&self, event: &NotificationEvent) -> Result<()> {
+        if !self.matches(event.notification()) {
+            return Ok(());
+        }
+
+        let action = action(event);
+        let notification = event.notification();
+
+        self.settings
+            .output
+            .print(event, format_args!("{action}: {notification:?}"))?;
+
+        if let Some(command) = &self.args.exec {
+            run_hook(command, action, notification).await;
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether a notification matches the filters.
+    fn matches(&self, notification: &Notification) -> bool {
+        let Watch {
+            tags,
+            acked,
+            unacked,
+            ..
+        } = self.args;
+
+        (tags.is_empty()
+            || tags.iter().any(|tag| notification.tags.contains(tag)))
+            && (!acked || notification.ack)
+            && (!unacked || !notification.ack)
+    }
+}
+
+impl Disconnection {
+    /// Reports the disconnection to the user.
+    fn report(&self) {
+        match self {
+            Self::Unsupported => {
+                warning!("The server cannot push events.");
+            }
+            Self::Closed => {
+                warning!("The server has closed the connection.");
+            }
+            Self::Error(error) => {
+                warning!("Lost the connection to the server: {error}.");
+            }
+        }
+    }
+}
+
+/// Returns the events turning `known` into `current`.
+fn diff(
+    known: &BTreeMap<usize, Notification>,
+    current: &[Notification],
+) -> Vec<NotificationEvent> {
+    let mut events = Vec::new();
+
+    for notification in current {
+        match known.get(&notification.id) {
+            None => {
+                events.push(NotificationEvent::Created(notification.clone()));
+            }
+            Some(previous) if !previous.ack && notification.ack => {
+                events.push(NotificationEvent::Acked(notification.clone()));
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (id, notification) in known {
+        if !current.iter().any(|current| current.id == *id) {
+            events.push(NotificationEvent::Deleted(notification.clone()));
+        }
+    }
+
+    events
+}
+
+/// Applies an event to the known notifications.
+///
+/// Returns whether the event is new, i.e. whether it has changed them.
+fn apply(
+    known: &mut BTreeMap<usize, Notification>,
+    event: &NotificationEvent,
+) -> bool {
+    match event {
+        // NOTE: The known notification is as recent as the event, if not more.
+        NotificationEvent::Created(notification)
+        | NotificationEvent::Restored(notification) => {
+            if known.contains_key(&notification.id) {
+                return false;
+            }
+
+            known.insert(notification.id, notification.clone());
+            true
+        }
+        NotificationEvent::Acked(notification) => known
+            .insert(notification.id, notification.clone())
+            .is_none_or(|previous| !previous.ack),
+        NotificationEvent::Deleted(notification) => {
+            known.remove(&notification.id).is_some()
+        }
+    }
+}
+
+/// Returns the past participle describing the event.
+fn action(event: &NotificationEvent) -> &'static str {
+    match event {
+        NotificationEvent::Created(_) => "created",
+        NotificationEvent::Acked(_) => "acknowledged",
+        NotificationEvent::Deleted(_) => "deleted",
+        NotificationEvent::Restored(_) => "restored",
+    }
+}
+
+/// Runs the hook for an event.
+async fn run_hook(command: &str, action: &str, notification: &Notification) {
+    tracing::debug!(command, action, ?notification, "running the hook");
+
+    let status = shell(command)
+        .env("NTF_EVENT", action)
+        .env("NTF_ID", notification.id.to_string())
+        .env("NTF_MESSAGE", &notification.message)
+        .env("NTF_ACK", notification.ack.to_string())
+        .env("NTF_TAGS", notification.tags.join(","))
+        .env("NTF_PRIORITY", notification.priority.name())
+        .status()
+        .await;
+
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => warning!("The hook has failed ({status})."),
+        Err(error) => warning!("Failed to run the hook: {error}."),
+    }
+}
+
+/// Builds a command running `command` in the system shell.
+fn shell(command: &str) -> tokio::process::Command {
+    #[cfg(windows)]
+    let (shell, flag) = ("cmd", "/C");
+    #[cfg(not(windows))]
+    let (shell, flag) = ("sh", "-c");
+
+    let mut shell = tokio::process::Command::new(shell);
+    shell.arg(flag).arg(command);
+    shell
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    fn notification(id: usize, ack: bool) -> Notification {
+        Notification {
+            ack,
+            ..Notification::new(id, format!("notification {id}"))
+        }
+    }
+
+    fn known(notifications: &[Notification]) -> BTreeMap<usize, Notification> {
+        notifications
+            .iter()
+            .map(|notification| (notification.id, notification.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn diff_returns_nothing_when_nothing_has_changed() {
+        let notifications = [notification(1, false), notification(2, true)];
+        let known = known(&notifications);
+
+        assert!(diff(&known, &notifications).is_empty());
+    }
+
+    #[test]
+    fn diff_returns_created_notifications() {
+        let known = known(&[notification(1, false)]);
+        let current = [notification(1, false), notification(2, false)];
+
+        let events = diff(&known, &current);
+
+        assert!(matches!(
+            events.as_slice(),
+            [NotificationEvent::Created(Notification { id: 2, .. })]
+        ));
+    }
+
+    #[test]
+    fn diff_returns_acknowledged_notifications() {
+        let known = known(&[notification(1, false)]);
+        let current = [notification(1, true)];
+
+        let events = diff(&known, &current);
+
+        assert!(matches!(
+            events.as_slice(),
+            [NotificationEvent::Acked(Notification { id: 1, .. })]
+        ));
+    }
+
+    #[test]
+    fn diff_returns_deleted_notifications() {
+        let known = known(&[notification(1, false), notification(2, false)]);
+        let current = [notification(2, false)];
+
+        let events = diff(&known, &current);
+
+        assert!(matches!(
+            events.as_slice(),
+            [NotificationEvent::Deleted(Notification { id: 1, .. })]
+        ));
+    }
+
+    #[test]
+    fn apply_skips_the_events_already_known() {
+        let mut known = known(&[notification(1, true), notification(2, false)]);
+
+        let events = [
+            NotificationEvent::Created(notification(1, false)),
+            NotificationEvent::Acked(notification(1, true)),
+            NotificationEvent::Deleted(notification(3, false)),
+        ];
+        for event in &events {
+            assert!(!apply(&mut known, event), "{event:?} is known");
+        }
+
+        let events = [
+            NotificationEvent::Created(notification(3, false)),
+            NotificationEvent::Acked(notification(2, true)),
+            NotificationEvent::Deleted(notification(1, true)),
+        ];
+        for event in &events {
+            assert!(apply(&mut known, event), "{event:?} is new");
+        }
+    }
+}
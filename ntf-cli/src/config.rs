@@ -0,0 +1,229 @@
+//! The configuration file for ntf-cli.
+
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use ntf_api::CreateNotificationPayload;
+
+use crate::command::OutputFormat;
+
+/// The contents of the configuration file.
+///
+/// The top-level keys define the default profile, and each table in
+/// `profiles` defines a named profile overriding the default one. Each table
+/// in `templates` defines a notification template for `ntf-cli create`, where
+/// `{name}` placeholders are replaced with variables and literal braces are
+/// written `{{` and `}}`:
+///
+/// ```toml
+/// url = "http://localhost:3000"
+///
+/// [profiles.prod]
+/// url = "https://ntf.example.com"
+/// token = "secret"
+/// output = "json"
+///
+/// [templates.disk_full]
+/// message = "The disk is full on {host}."
+/// tags = ["disk", "{host}"]
+/// priority = "high"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// The default profile.
+    #[serde(flatten)]
+    default: Profile,
+    /// The named profiles.
+    #[serde(default)]
+    profiles: BTreeMap<String, Profile>,
+    /// The notification templates.
+    #[serde(default)]
+    templates: BTreeMap<String, CreateNotificationPayload>,
+}
+
+/// A configuration profile.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct Profile {
+    /// The API base URL.
+    pub url: Option<String>,
+    /// The API token.
+    pub token: Option<String>,
+    /// The default output format.
+    pub output: Option<OutputFormat>,
+}
+
+/// Errors that can occur when loading the configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    /// The configuration file cannot be read.
+    #[error("failed to read the configuration file {}", path.display())]
+    ReadError {
+        /// The path of the configuration file.
+        path: PathBuf,
+        /// The source of the error.
+        source: io::Error,
+    },
+    /// The configuration file is invalid.
+    #[error("invalid configuration file {}", path.display())]
+    ParseError {
+        /// The path of the configuration file.
+        path: PathBuf,
+        /// The source of the error.
+        source: Box<toml::de::Error>,
+    },
+    /// The requested profile does not exist.
+    #[error("unknown profile `{name}`")]
+    UnknownProfile {
+        /// The name of the profile.
+        name: String,
+        /// The names of the available profiles.
+        available: Vec<String>,
+    },
+}
+
+impl Config {
+    /// Loads the configuration from `path`, or from the default location.
+    ///
+    /// A missing configuration file at the default location is not an error:
+    /// an empty configuration is returned instead.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Self::load_from(path),
+            None => match default_path() {
+                Some(path) if path.exists() => Self::load_from(&path),
+                _ => Ok(Self::default()),
+            },
+        }
+    }
+
+    /// Loads the configuration from `path`.
+    fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        tracing::debug!(?path, "loading the configuration");
+
+        let contents = fs::read_to_string(path).map_err(|source| {
+            ConfigError::ReadError {
+                path: path.to_owned(),
+                source,
+            }
+        })?;
+
+        Self::parse(&contents).map_err(|source| ConfigError::ParseError {
+            path: path.to_owned(),
+            source: Box::new(source),
+        })
+    }
+
+    /// Parses the configuration from a TOML string.
+    fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Returns the profile with the given name, merged with the default one.
+    ///
+    /// When `name` is `None`, the default profile is returned.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, ConfigError> {
+        let Some(name) = name else {
+            return Ok(self.default.clone());
+        };
+
+        let profile = self.profiles.get(name).ok_or_else(|| {
+            ConfigError::UnknownProfile {
+                name: name.to_owned(),
+                available: self.profiles.keys().cloned().collect(),
+            }
+        })?;
+
+        Ok(Profile {
+            url: profile.url.clone().or_else(|| self.default.url.clone()),
+            token: profile.token.clone().or_else(|| self.default.token.clone()),
+            output: profile.output.or(self.default.output),
+        })
+    }
+
+    /// Returns the notification templates.
+    pub fn templates(&self) -> &BTreeMap<String, CreateNotificationPayload> {
+        &self.templates
+    }
+}
+
+/// Returns the default path of the configuration file.
+///
+/// This is `$XDG_CONFIG_HOME/ntf/config.toml`, where `XDG_CONFIG_HOME`
+/// defaults to `~/.config`.
+fn default_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".config"))
+        })?;
+
+    Some(config_home.join("ntf").join("config.toml"))
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    const CONFIG: &str = r#"
+        url = "http://localhost:3000"
+        output = "json"
+
+        [profiles.prod]
+        url = "https://ntf.example.com"
+        token = "secret"
+
+        [profiles.staging]
+        token = "staging-secret"
+        output = "text"
+    "#;
+
+    #[test]
+    fn profile_returns_the_default_profile_without_name() {
+        let config = Config::parse(CONFIG).unwrap();
+        let profile = config.profile(None).unwrap();
+
+        assert_eq!(profile.url.as_deref(), Some("http://localhost:3000"));
+        assert_eq!(profile.token, None);
+        assert_eq!(profile.output, Some(OutputFormat::Json));
+    }
+
+    #[test]
+    fn profile_overrides_the_default_profile() {
+        let config = Config::parse(CONFIG).unwrap();
+        let profile = config.profile(Some("prod")).unwrap();
+
+        assert_eq!(profile.url.as_deref(), Some("https://ntf.example.com"));
+        assert_eq!(profile.token.as_deref(), Some("secret"));
+        assert_eq!(profile.output, Some(OutputFormat::Json));
+    }
+
+    #[test]
+    fn profile_falls_back_to_the_default_url() {
+        let config = Config::parse(CONFIG).unwrap();
+        let profile = config.profile(Some("staging")).unwrap();
+
+        assert_eq!(profile.url.as_deref(), Some("http://localhost:3000"));
+        assert_eq!(profile.output, Some(OutputFormat::Text));
+    }
+
+    #[test]
+    fn profile_returns_an_error_on_unknown_profile() {
+        let config = Config::parse(CONFIG).unwrap();
+        let error = config.profile(Some("dev")).unwrap_err();
+
+        assert!(matches!(
+            error,
+            ConfigError::UnknownProfile { name, available }
+                if name == "dev" && available == ["prod", "staging"]
+        ));
+    }
+}
@@ -1,6 +1,7 @@
 //! A CLI client for the notification API.
 
 mod command;
+mod config;
 
 #[doc(hidden)]
 pub use command::NtfCli;
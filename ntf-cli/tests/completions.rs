@@ -0,0 +1,72 @@
+//! CLI tests for `ntf-cli completions` and `ntf-cli man`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+use std::process::Command;
+
+use assert_cmd::cargo_bin;
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+const TIMEOUT: Option<u64> = Some(1_000);
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn ntf_cli(args: &[&str]) -> Result<Command> {
+    let mut cmd = Command::new(cargo_bin!("ntf-cli"));
+    cmd.env("NO_COLOR", "true").args(args);
+
+    Ok(cmd)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                Completions                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn generates_completions_for_each_shell() -> Result<()> {
+    for (shell, expected) in [
+        ("bash", "_ntf__cli()"),
+        ("zsh", "#compdef ntf-cli"),
+        ("fish", "function __fish_ntf_cli_global_optspecs"),
+        ("elvish", "set edit:completion:arg-completer[ntf-cli]"),
+    ] {
+        let mut process =
+            spawn_command(ntf_cli(&["completions", shell])?, TIMEOUT)?;
+
+        process.exp_string(expected)?;
+        process.exp_eof()?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn generates_dynamic_completions_registration() -> Result<()> {
+    let command = ntf_cli(&["completions", "bash", "--dynamic"])?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+
+    process.exp_string("COMPLETE=\"bash\"")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                    Man                                     //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn generates_the_man_page() -> Result<()> {
+    let mut process = spawn_command(ntf_cli(&["man"])?, TIMEOUT)?;
+
+    process.exp_string(".TH ntf-cli 1")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! CLI tests for the configuration of `ntf-cli`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+use std::{fs, path::PathBuf, process::Command};
+
+use assert_cmd::cargo_bin;
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+const TIMEOUT: Option<u64> = Some(5_000);
+
+/// A URL on which no server is expected to listen.
+const UNREACHABLE_URL: &str = "http://127.0.0.1:1";
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn ntf_cli(args: &[&str]) -> Result<Command> {
+    let mut cmd = Command::new(cargo_bin!("ntf-cli"));
+    cmd.env("NO_COLOR", "true")
+        .env_remove("NTF_URL")
+        .env_remove("NTF_TOKEN")
+        .env_remove("NTF_PROFILE")
+        .env_remove("NTF_CONFIG")
+        .env(
+            "XDG_CONFIG_HOME",
+            std::env::temp_dir().join("ntf-cli-tests"),
+        )
+        .args(args);
+
+    Ok(cmd)
+}
+
+fn write_config(name: &str, contents: &str) -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("ntf-cli-{name}.toml"));
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                    URL                                     //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn uses_the_url_from_the_environment() -> Result<()> {
+    let mut command = ntf_cli(&["list"])?;
+    command.env("NTF_URL", UNREACHABLE_URL);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+
+    process.exp_string(&format!("Is ntf-srv running at {UNREACHABLE_URL}?"))?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn uses_the_url_from_the_profile() -> Result<()> {
+    let config = write_config(
+        "profile-url",
+        &format!("[profiles.test]\nurl = \"{UNREACHABLE_URL}\"\n"),
+    )?;
+
+    let command = ntf_cli(&[
+        "list",
+        "--profile",
+        "test",
+        "--config",
+        config.to_str().unwrap(),
+    ])?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+
+    process.exp_string(&format!("Is ntf-srv running at {UNREACHABLE_URL}?"))?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Profiles                                  //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn lists_available_profiles_on_unknown_profile() -> Result<()> {
+    let config = write_config(
+        "unknown-profile",
+        "[profiles.prod]\n[profiles.staging]\n",
+    )?;
+
+    let command = ntf_cli(&[
+        "list",
+        "--profile",
+        "dev",
+        "--config",
+        config.to_str().unwrap(),
+    ])?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+
+    process.exp_string("Error: unknown profile `dev`.")?;
+    process.exp_string("Available profiles: prod, staging.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exits_with_config_on_unknown_profile() -> Result<()> {
+    let output = ntf_cli(&["list", "--profile", "dev"])?.output()?;
+
+    assert_eq!(output.status.code(), Some(exitcode::CONFIG));
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                 Templates                                  //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn lists_available_templates_on_unknown_template() -> Result<()> {
+    let config = write_config(
+        "unknown-template",
+        "[templates.disk_full]\nmessage = \"The disk is full.\"\n",
+    )?;
+
+    let command = ntf_cli(&[
+        "create",
+        "--template",
+        "backup",
+        "--config",
+        config.to_str().unwrap(),
+    ])?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+
+    process.exp_string("Error: unknown template `backup`.")?;
+    process.exp_string("Available templates: disk_full.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exits_with_usage_on_missing_template_variable() -> Result<()> {
+    let config = write_config(
+        "missing-variable",
+        "[templates.disk_full]\nmessage = \"The disk is full on {host}.\"\n",
+    )?;
+
+    let output = ntf_cli(&[
+        "create",
+        "--template",
+        "disk_full",
+        "--config",
+        config.to_str().unwrap(),
+    ])?
+    .output()?;
+
+    assert_eq!(output.status.code(), Some(exitcode::USAGE));
+
+    Ok(())
+}
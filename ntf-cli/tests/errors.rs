@@ -0,0 +1,53 @@
+//! CLI tests for the error handling of `ntf-cli`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+use std::process::Command;
+
+use assert_cmd::cargo_bin;
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+const TIMEOUT: Option<u64> = Some(5_000);
+
+/// A URL on which no server is expected to listen.
+const UNREACHABLE_URL: &str = "http://127.0.0.1:1";
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn ntf_cli(args: &[&str]) -> Result<Command> {
+    let mut cmd = Command::new(cargo_bin!("ntf-cli"));
+    cmd.env("NO_COLOR", "true").args(args);
+
+    Ok(cmd)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                             Connection errors                              //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn hints_at_the_server_url_on_connection_error() -> Result<()> {
+    let command = ntf_cli(&["list", "--url", UNREACHABLE_URL])?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+
+    process.exp_string("Error: could not connect to the server.")?;
+    process.exp_string(&format!("Is ntf-srv running at {UNREACHABLE_URL}?"))?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exits_with_unavailable_on_connection_error() -> Result<()> {
+    let output = ntf_cli(&["get", "1", "--url", UNREACHABLE_URL])?.output()?;
+
+    assert_eq!(output.status.code(), Some(exitcode::UNAVAILABLE));
+
+    Ok(())
+}
@@ -0,0 +1,753 @@
+//! Inbound adapters, turning the payloads of alert sources into notifications.
+//!
+//! Three sources are supported:
+//!
+//! - `POST /ingest/alertmanager`, for the webhooks of Prometheus Alertmanager;
+//! - `POST /ingest/github`, for GitHub-style webhooks, with the event in the
+//!   `X-GitHub-Event` header and the HMAC-SHA256 signature of the body in the
+//!   `X-Hub-Signature-256` header, rejected unless a secret is configured;
+//! - `POST /ingest/generic`, for any JSON payload, mapped by templates given
+//!   in the query, such as `?title=Disk full on {$.host}&priority={$.level}`.
+//!
+//! Alerts are identified by a key, so that a resolved alert acknowledges the
+//! notification created when it fired, and an alert firing again is not
+//! notified twice, even when its notification has been acknowledged, until it
+//! is resolved.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Router,
+    body::Bytes,
+    extract::{Extension, Query, State},
+    http::HeaderMap,
+    response::Json,
+    routing::post,
+};
+use axum_extra::extract::WithRejection;
+use clap::Args;
+use hmac::{Hmac, Mac as _};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::Sha256;
+
+use ntf_api_types::{
+    Action, ActionKind, CreateNotificationError, CreateNotificationPayload,
+    Format, Notification, Priority,
+};
+use ntf_poc_helpers::tracing::LogResult as _;
+
+use crate::{AppState, is_http_url, limits::Clients, validation::Validator};
+
+/// The options of the inbound adapters.
+#[derive(Debug, Clone, Args)]
+pub struct IngestArgs {
+    /// The secret GitHub-style webhooks are signed with.
+    ///
+    /// They are rejected when it is not set.
+    #[arg(long, env = "NTF_GITHUB_SECRET", hide_env_values = true)]
+    pub github_secret: Option<String>,
+}
+
+/// An alert, as mapped from an inbound payload.
+#[derive(Debug)]
+struct Alert {
+    /// The key identifying the alert across its updates, if any.
+    key: Option<String>,
+    /// Whether the alert is resolved.
+    resolved: bool,
+    /// The notification to create while the alert fires.
+    payload: CreateNotificationPayload,
+}
+
+/// The webhook payload of Prometheus Alertmanager.
+#[derive(Debug, Deserialize)]
+struct AlertmanagerPayload {
+    /// The alerts of the group.
+    alerts: Vec<AlertmanagerAlert>,
+}
+
+/// An alert sent by Prometheus Alertmanager.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AlertmanagerAlert {
+    /// The status, `firing` or `resolved`.
+    status: String,
+    /// The labels, including `alertname` and usually `severity`.
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    /// The annotations, usually including `summary` and `description`.
+    #[serde(default)]
+    annotations: BTreeMap<String, String>,
+    /// The URL of the source of the alert.
+    #[serde(default, rename = "generatorURL")]
+    generator_url: String,
+    /// The fingerprint identifying the alert.
+    #[serde(default)]
+    fingerprint: String,
+}
+
+/// The mapping of a generic JSON payload, given in the query.
+///
+/// Each field is a template, where `{$.path}` placeholders are replaced by the
+/// values selected in the payload by their JSON path.
+#[derive(Debug, Deserialize)]
+struct Mapping {
+    /// The title of the notification.
+    title: String,
+    /// The details shown below the title.
+    message: Option<String>,
+    /// The priority, or a severity such as `critical` or `warning`.
+    priority: Option<String>,
+    /// The tags, separated by commas.
+    tags: Option<String>,
+    /// The key identifying the alert.
+    key: Option<String>,
+    /// Whether the alert is resolved, when rendered to `true` or `resolved`.
+    resolved: Option<String>,
+    /// A URL to open from the notification.
+    url: Option<String>,
+}
+
+/// Returns the routes to ingest alerts.
+pub fn routes() -> Router<Arc<Mutex<AppState>>> {
+    Router::new()
+        .route("/ingest/alertmanager", post(ingest_alertmanager))
+        .route("/ingest/github", post(ingest_github))
+        .route("/ingest/generic", post(ingest_generic))
+}
+
+/// Ingests an Alertmanager webhook.
+#[tracing::instrument(skip(state, clients))]
+async fn ingest_alertmanager(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Extension(clients): Extension<Clients>,
+    WithRejection(Json(payload), _): WithRejection<
+        Json<AlertmanagerPayload>,
+        CreateNotificationError,
+    >,
+) -> Result<Json<Vec<Notification>>, CreateNotificationError> {
+    let alerts = payload
+        .alerts
+        .into_iter()
+        .map(AlertmanagerAlert::into_alert)
+        .collect();
+    let notifications = {
+        let mut state = state.lock().expect("poisoned lock");
+        let notifications = ingest(&mut state, alerts).log_err()?;
+        state.charge(&clients, notifications.len());
+        notifications
+    };
+
+    tracing::info!(?notifications, "INGEST ALERTMANAGER");
+    Ok(Json(notifications))
+}
+
+/// Ingests a GitHub-style webhook, once its signature is verified.
+#[tracing::instrument(skip(state, clients, body))]
+async fn ingest_github(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Extension(clients): Extension<Clients>,
+    headers: HeaderMap,
+    WithRejection(body, _): WithRejection<Bytes, CreateNotificationError>,
+) -> Result<Json<Vec<Notification>>, CreateNotificationError> {
+    let signature = headers
+        .get("X-Hub-Signature-256")
+        .and_then(|signature| signature.to_str().ok());
+    let verified = state
+        .lock()
+        .expect("poisoned lock")
+        .github_secret
+        .as_deref()
+        .zip(signature)
+        .is_some_and(|(secret, signature)| {
+            verify_signature(secret, &body, signature)
+        });
+    if !verified {
+        return Err(CreateNotificationError::InvalidSignature).log_err();
+    }
+
+    let event = headers
+        .get("X-GitHub-Event")
+        .and_then(|event| event.to_str().ok())
+        .ok_or_else(|| {
+            CreateNotificationError::PayloadError(String::from(
+                "missing X-GitHub-Event header",
+            ))
+        })
+        .log_err()?;
+    let body = serde_json::from_slice::<Value>(&body)
+        .map_err(|error| {
+            CreateNotificationError::PayloadError(format!(
+                "invalid JSON: {error}"
+            ))
+        })
+        .log_err()?;
+
+    let alerts = github_alert(event, &body).into_iter().collect();
+    let notifications = {
+        let mut state = state.lock().expect("poisoned lock");
+        let notifications = ingest(&mut state, alerts).log_err()?;
+        state.charge(&clients, notifications.len());
+        notifications
+    };
+
+    tracing::info!(?notifications, "INGEST GITHUB");
+    Ok(Json(notifications))
+}
+
+/// Ingests a generic JSON payload, or an array of them.
+#[tracing::instrument(skip(state, clients))]
+async fn ingest_generic(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Extension(clients): Extension<Clients>,
+    WithRejection(Query(mapping), _): WithRejection<
+        Query<Mapping>,
+        CreateNotificationError,
+    >,
+    WithRejection(Json(body), _): WithRejection<
+        Json<Value>,
+        CreateNotificationError,
+    >,
+) -> Result<Json<Vec<Notification>>, CreateNotificationError> {
+    let alerts = match &body {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| mapping.apply(item))
+            .collect::<Result<_, _>>(),
+        body => mapping.apply(body).map(|alert| vec![alert]),
+    }
+    .map_err(CreateNotificationError::PayloadError)
+    .log_err()?;
+    let notifications = {
+        let mut state = state.lock().expect("poisoned lock");
+        let notifications = ingest(&mut state, alerts).log_err()?;
+        state.charge(&clients, notifications.len());
+        notifications
+    };
+
+    tracing::info!(?notifications, "INGEST GENERIC");
+    Ok(Json(notifications))
+}
+
+/// Creates the notifications of the firing alerts, and acknowledges the ones
+/// of the resolved alerts.
+///
+/// Returns the created and acknowledged notifications, or an error without
+/// ingesting any alert if one of them has an invalid message, reported on the
+/// `[index].message` field.
+fn ingest(
+    state: &mut AppState,
+    alerts: Vec<Alert>,
+) -> Result<Vec<Notification>, CreateNotificationError> {
+    let mut validator = Validator::default();
+    for (index, alert) in alerts.iter().enumerate() {
+        validator.message(
+            &format!("[{index}].message"),
+            &alert.payload.message,
+            state.limits.max_message_length,
+        );
+    }
+    validator.finish()?;
+
+    let mut notifications = Vec::new();
+
+    for alert in alerts {
+        // NOTE: The keys of the removed notifications are pruned, so an alert
+        // is notified again only once resolved, or once its notification is
+        // gone.
+        let notified = alert
+            .key
+            .as_ref()
+            .and_then(|key| state.alerts.get(key).copied());
+
+        match (alert.resolved, notified) {
+            (true, notified) => {
+                if let Some(key) = &alert.key {
+                    state.alerts.remove(key);
+                }
+                notifications.extend(notified.and_then(|id| state.ack(id)));
+            }
+            // NOTE: Alert sources repeat the firing alerts, which are only
+            // notified once.
+            (false, Some(_)) => {}
+            (false, None) => {
+                let notification = state.create(alert.payload);
+                if let Some(key) = alert.key {
+                    state.alerts.insert(key, notification.id);
+                }
+                notifications.push(notification);
+            }
+        }
+    }
+
+    Ok(notifications)
+}
+
+/// Verifies the `sha256=<hex>` HMAC-SHA256 signature of a body.
+fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Some(signature) = signature.strip_prefix("sha256=").and_then(hex)
+    else {
+        return false;
+    };
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any size");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Decodes an hexadecimal string.
+fn hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| {
+            let byte = hex.get(index..index + 2)?;
+            u8::from_str_radix(byte, 16).ok()
+        })
+        .collect()
+}
+
+impl AlertmanagerAlert {
+    /// Maps the Alertmanager alert to an alert.
+    fn into_alert(self) -> Alert {
+        let name = self.labels.get("alertname").map_or("Alert", String::as_str);
+        let title = match self.labels.get("instance") {
+            Some(instance) => format!("{name} on {instance}"),
+            None => name.to_owned(),
+        };
+        let details = ["summary", "description"]
+            .iter()
+            .filter_map(|annotation| self.annotations.get(*annotation))
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        let priority = self
+            .labels
+            .get("severity")
+            .map_or_else(Priority::default, |severity| priority(severity));
+        let key = if self.fingerprint.is_empty() {
+            format!("alertmanager:{:?}", self.labels)
+        } else {
+            format!("alertmanager:{}", self.fingerprint)
+        };
+
+        Alert {
+            key: Some(key),
+            resolved: self.status == "resolved",
+            payload: payload(
+                &title,
+                &details,
+                priority,
+                vec![String::from("alertmanager"), name.to_owned()],
+                &self.generator_url,
+            ),
+        }
+    }
+}
+
+/// Maps a GitHub-style event to an alert, if it is worth a notification.
+///
+/// Issues and pull requests fire when opened and resolve when closed, and
+/// workflow runs fire on failure and resolve on success.
+fn github_alert(event: &str, body: &Value) -> Option<Alert> {
+    let repository = text(body, "/repository/full_name");
+    let action = text(body, "/action");
+    let tags = vec![
+        String::from("github"),
+        repository.to_owned(),
+        event.to_owned(),
+    ];
+
+    match event {
+        "issues" | "pull_request" => github_item_alert(event, body, tags),
+        "workflow_run" if action == "completed" => {
+            let run = body.get("workflow_run")?;
+            let name = text(run, "/name");
+            let branch = text(run, "/head_branch");
+            let resolved = match text(run, "/conclusion") {
+                "success" => true,
+                "failure" | "timed_out" | "startup_failure" => false,
+                _ => return None,
+            };
+            let title =
+                format!("Workflow {name} failed on {branch} in {repository}");
+
+            Some(Alert {
+                key: Some(format!("github:{repository}:{name}@{branch}")),
+                resolved,
+                payload: payload(
+                    &title,
+                    "",
+                    Priority::High,
+                    tags,
+                    text(run, "/html_url"),
+                ),
+            })
+        }
+        "ping" | "workflow_run" => None,
+        "push" => {
+            let commits = body
+                .get("commits")
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len);
+            let title = format!(
+                "{} pushed {commits} commit(s) to {} in {repository}",
+                text(body, "/pusher/name"),
+                text(body, "/ref"),
+            );
+
+            Some(Alert {
+                key: None,
+                resolved: false,
+                payload: payload(
+                    &title,
+                    text(body, "/head_commit/message"),
+                    Priority::Low,
+                    tags,
+                    text(body, "/compare"),
+                ),
+            })
+        }
+        _ => {
+            let title = if action.is_empty() {
+                format!("GitHub event {event} in {repository}")
+            } else {
+                format!("GitHub event {event} {action} in {repository}")
+            };
+
+            Some(Alert {
+                key: None,
+                resolved: false,
+                payload: payload(
+                    &title,
+                    "",
+                    Priority::Low,
+                    tags,
+                    text(body, "/repository/html_url"),
+                ),
+            })
+        }
+    }
+}
+
+/// Maps a GitHub-style event of an issue or a pull request to an alert.
+fn github_item_alert(
+    event: &str,
+    body: &Value,
+    tags: Vec<String>,
+) -> Option<Alert> {
+    let repository = text(body, "/repository/full_name");
+    let action = text(body, "/action");
+    let (kind, item) = if event == "issues" {
+        ("Issue", body.get("issue")?)
+    } else {
+        ("Pull request", body.get("pull_request")?)
+    };
+    let number = item.get("number")?;
+    let resolved = match action {
+        "opened" | "reopened" => false,
+        "closed" => true,
+        _ => return None,
+    };
+    let title = format!(
+        "{kind} #{number} {action} in {repository}: {}",
+        text(item, "/title")
+    );
+
+    Some(Alert {
+        key: Some(format!("github:{repository}#{number}")),
+        resolved,
+        payload: payload(
+            &title,
+            text(item, "/body"),
+            Priority::Normal,
+            tags,
+            text(item, "/html_url"),
+        ),
+    })
+}
+
+impl Mapping {
+    /// Maps a generic JSON payload to an alert.
+    fn apply(&self, body: &Value) -> Result<Alert, String> {
+        let render_opt = |field: Option<&String>| {
+            field.map_or_else(
+                || Ok(String::new()),
+                |template| render(template, body),
+            )
+        };
+
+        let title = render(&self.title, body)?;
+        let details = render_opt(self.message.as_ref())?;
+        let priority = priority(&render_opt(self.priority.as_ref())?);
+        let tags = self
+            .tags
+            .iter()
+            .flat_map(|tags| tags.split(','))
+            .map(|tag| render(tag.trim(), body))
+            .collect::<Result<_, _>>()?;
+        let key = Some(render_opt(self.key.as_ref())?)
+            .filter(|key| !key.is_empty())
+            .map(|key| format!("generic:{key}"));
+        let resolved = matches!(
+            render_opt(self.resolved.as_ref())?.to_lowercase().as_str(),
+            "true" | "resolved"
+        );
+        let url = render_opt(self.url.as_ref())?;
+
+        Ok(Alert {
+            key,
+            resolved,
+            payload: payload(&title, &details, priority, tags, &url),
+        })
+    }
+}
+
+/// Builds the payload of a notification.
+///
+/// The title is the first line of the message, followed by the details. The
+/// URL, if it is an HTTP one, is added as an action to open it.
+pub fn payload(
+    title: &str,
+    details: &str,
+    priority: Priority,
+    tags: Vec<String>,
+    url: &str,
+) -> CreateNotificationPayload {
+    let message = if details.trim().is_empty() {
+        title.to_owned()
+    } else {
+        format!("{title}\n\n{details}")
+    };
+    let actions = is_http_url(url)
+        .then(|| Action {
+            name: String::from("open"),
+            label: String::from("Open"),
+            kind: ActionKind::Url {
+                url: url.to_owned(),
+            },
+            outcome: None,
+        })
+        .into_iter()
+        .collect();
+
+    CreateNotificationPayload {
+        message,
+        format: Format::Plain,
+        tags: tags.into_iter().filter(|tag| !tag.is_empty()).collect(),
+        priority,
+        actions,
+    }
+}
+
+/// Maps a severity, as used by the alert sources, to a priority.
+///
+/// The names of the priorities are accepted too, and the other values are
+/// mapped to the default priority.
+fn priority(severity: &str) -> Priority {
+    match severity.trim().to_lowercase().as_str() {
+        "critical" | "fatal" | "emergency" => Priority::Urgent,
+        "error" | "major" | "warning" => Priority::High,
+        "info" | "minor" => Priority::Low,
+        "debug" | "none" => Priority::Min,
+        other => other.parse().unwrap_or_default(),
+    }
+}
+
+/// Returns the string at a JSON pointer, or an empty string.
+fn text<'v>(value: &'v Value, pointer: &str) -> &'v str {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+}
+
+/// Renders a template, replacing the `{$.path}` placeholders with the values
+/// selected by their JSON path.
+///
+/// Strings are inserted as is, other values as JSON, and missing values as
+/// nothing.
+fn render(template: &str, body: &Value) -> Result<String, String> {
+    let mut rendered = String::new();
+    let mut rest = template;
+
+    while let Some((before, after)) = rest.split_once("{$") {
+        let (path, after) = after.split_once('}').ok_or_else(|| {
+            format!("unclosed placeholder in template `{template}`")
+        })?;
+
+        rendered.push_str(before);
+        match select(body, path)? {
+            Some(Value::String(string)) => rendered.push_str(string),
+            Some(Value::Null) | None => {}
+            Some(value) => rendered.push_str(&value.to_string()),
+        }
+        rest = after;
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Selects a value by its JSON path, without the leading `$`.
+///
+/// Only member names and array indices are supported, as in
+/// `.alerts[0].labels.severity`.
+fn select<'v>(
+    value: &'v Value,
+    path: &str,
+) -> Result<Option<&'v Value>, String> {
+    let invalid = || format!("invalid JSONPath `${path}`");
+    let mut selected = Some(value);
+    let mut rest = path;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let (name, after) = after
+                .find(['.', '['])
+                .map_or((after, ""), |end| after.split_at(end));
+            if name.is_empty() {
+                return Err(invalid());
+            }
+
+            selected = selected.and_then(|value| value.get(name));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let (index, after) = after.split_once(']').ok_or_else(invalid)?;
+            let index =
+                index.trim().parse::<usize>().ok().ok_or_else(invalid)?;
+
+            selected = selected.and_then(|value| value.get(index));
+            rest = after;
+        } else {
+            return Err(invalid());
+        }
+    }
+
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_replaces_placeholders() {
+        let body = json!({
+            "host": "db1",
+            "checks": [{"usage": 97_i32}],
+            "missing": null,
+        });
+
+        assert_eq!(
+            render("{$.host} at {$.checks[0].usage}%{$.missing}", &body)
+                .unwrap(),
+            "db1 at 97%"
+        );
+        render("{$.host", &body).unwrap_err();
+        render("{$host}", &body).unwrap_err();
+    }
+
+    #[test]
+    fn priority_maps_severities() {
+        assert_eq!(priority("critical"), Priority::Urgent);
+        assert_eq!(priority("Warning"), Priority::High);
+        assert_eq!(priority("low"), Priority::Low);
+        assert_eq!(priority("unknown"), Priority::Normal);
+    }
+
+    #[test]
+    fn resolved_alerts_are_acked() {
+        let mut state = AppState::default();
+        let alert = |status: &str| {
+            serde_json::from_value::<AlertmanagerAlert>(json!({
+                "status": status,
+                "labels": {"alertname": "DiskFull", "severity": "critical"},
+                "annotations": {"summary": "The disk is full."},
+                "fingerprint": "abc",
+            }))
+            .unwrap()
+            .into_alert()
+        };
+
+        let created = ingest(&mut state, vec![alert("firing")]).unwrap();
+        let repeated = ingest(&mut state, vec![alert("firing")]).unwrap();
+        let resolved = ingest(&mut state, vec![alert("resolved")]).unwrap();
+
+        assert_eq!(created.len(), 1);
+        let notification = created.first().unwrap();
+        assert_eq!(notification.message, "DiskFull\n\nThe disk is full.");
+        assert_eq!(notification.priority, Priority::Urgent);
+        assert!(repeated.is_empty());
+        assert!(resolved.first().is_some_and(|ntf| ntf.ack));
+
+        let fired_again = ingest(&mut state, vec![alert("firing")]).unwrap();
+        assert_eq!(fired_again.len(), 1);
+    }
+
+    #[test]
+    fn acked_alerts_are_not_notified_again_while_firing() {
+        let mut state = AppState::default();
+        let alert = || Alert {
+            key: Some(String::from("abc")),
+            resolved: false,
+            payload: payload("Disk full", "", Priority::High, Vec::new(), ""),
+        };
+
+        let created = ingest(&mut state, vec![alert()]).unwrap();
+        state.ack(created.first().unwrap().id);
+        let repeated = ingest(&mut state, vec![alert()]).unwrap();
+        assert!(repeated.is_empty());
+
+        // NOTE: The key is pruned with its notification.
+        state.limits.max_notifications = 0;
+        state.evict();
+        assert!(state.alerts.is_empty());
+        let recreated = ingest(&mut state, vec![alert()]).unwrap();
+        assert_eq!(recreated.len(), 1);
+    }
+
+    #[test]
+    fn invalid_alerts_are_reported_by_index() {
+        let mut state = AppState::default();
+        let alert = |message: &str| Alert {
+            key: None,
+            resolved: false,
+            payload: payload(message, "", Priority::Normal, Vec::new(), ""),
+        };
+
+        let error = ingest(&mut state, vec![alert("Disk full"), alert("")])
+            .unwrap_err();
+
+        let CreateNotificationError::ValidationErrors(errors) = error else {
+            unreachable!("{error:?} is not a validation error");
+        };
+        let fields = errors.iter().map(|error| error.field.as_str());
+        assert_eq!(fields.collect::<Vec<_>>(), ["[1].message"]);
+        assert!(state.notifications.is_empty());
+    }
+
+    #[test]
+    fn verify_signature_checks_the_hmac() {
+        let body = br#"{"zen":"Keep it logically awesome."}"#;
+        let signature = crate::webhooks::sign("secret", body);
+
+        assert!(verify_signature("secret", body, &signature));
+        assert!(!verify_signature("other", body, &signature));
+        assert!(!verify_signature("secret", b"{}", &signature));
+        assert!(!verify_signature("secret", body, "sha256=zz"));
+        assert!(!verify_signature("secret", body, "sha1=00"));
+    }
+}
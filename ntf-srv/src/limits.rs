@@ -0,0 +1,395 @@
+//! The limits protecting the service from buggy or malicious producers.
+//!
+//! The routes creating notifications are rate limited with token buckets, one
+//! per client IP address and one per bearer token, so that a request is only
+//! accepted when both buckets have a token left. Tokens are not verified by the
+//! service, hence the IP address being limited too.
+//!
+//! Each request takes a token, and each notification created in a batch beyond
+//! the first one takes another token afterwards, possibly leaving the buckets
+//! in debt so that the client has to wait for them to refill. The debt is
+//! capped to a minute of refill, so that a large import or batch does not
+//! block the client for long.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use clap::Args;
+
+use ntf_api_types::CreateNotificationError;
+use ntf_poc_helpers::tracing::LogResult as _;
+
+use crate::AppState;
+
+/// The default number of notifications a client can create per minute.
+const DEFAULT_RATE: u32 = 60;
+
+/// The default number of notifications a client can create in a burst.
+const DEFAULT_BURST: u32 = 20;
+
+/// The default maximum length of a message, in characters.
+const DEFAULT_MAX_MESSAGE_LENGTH: usize = 4096;
+
+/// The default maximum size of a request body, in bytes.
+const DEFAULT_MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// The default maximum size of an import body, in bytes.
+const DEFAULT_MAX_IMPORT_SIZE: usize = 16 * 1024 * 1024;
+
+/// The default maximum number of stored notifications.
+const DEFAULT_MAX_NOTIFICATIONS: usize = 10_000;
+
+/// The number of tracked clients above which the full buckets are dropped.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// The limits of the service.
+#[derive(Debug, Clone, Copy, Args)]
+pub struct Limits {
+    /// The number of notifications a client can create per minute.
+    #[arg(
+        long = "rate-limit",
+        env = "NTF_RATE_LIMIT",
+        value_name = "PER_MINUTE",
+        default_value_t = DEFAULT_RATE,
+    )]
+    pub rate: u32,
+    /// The number of notifications a client can create in a burst.
+    #[arg(
+        long = "rate-burst",
+        env = "NTF_RATE_BURST",
+        default_value_t = DEFAULT_BURST,
+    )]
+    pub burst: u32,
+    /// The maximum length of a message, in characters.
+    #[arg(
+        long,
+        env = "NTF_MAX_MESSAGE_LENGTH",
+        default_value_t = DEFAULT_MAX_MESSAGE_LENGTH,
+    )]
+    pub max_message_length: usize,
+    /// The maximum size of a request body, in bytes.
+    #[arg(
+        long,
+        env = "NTF_MAX_BODY_SIZE",
+        value_name = "BYTES",
+        default_value_t = DEFAULT_MAX_BODY_SIZE,
+    )]
+    pub max_body_size: usize,
+    /// The maximum size of an import body, in bytes.
+    #[arg(
+        long,
+        env = "NTF_MAX_IMPORT_SIZE",
+        value_name = "BYTES",
+        default_value_t = DEFAULT_MAX_IMPORT_SIZE,
+    )]
+    pub max_import_size: usize,
+    /// The maximum number of stored notifications, above which the ones in
+    /// the trash, then the acknowledged ones, then the oldest ones are
+    /// evicted.
+    #[arg(
+        long,
+        env = "NTF_MAX_NOTIFICATIONS",
+        default_value_t = DEFAULT_MAX_NOTIFICATIONS,
+    )]
+    pub max_notifications: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            rate: DEFAULT_RATE,
+            burst: DEFAULT_BURST,
+            max_message_length: DEFAULT_MAX_MESSAGE_LENGTH,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            max_import_size: DEFAULT_MAX_IMPORT_SIZE,
+            max_notifications: DEFAULT_MAX_NOTIFICATIONS,
+        }
+    }
+}
+
+/// The clients a request is made by, as identified for rate limiting.
+#[derive(Debug, Clone)]
+pub struct Clients(Vec<Client>);
+
+/// A client of the service, as identified for rate limiting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Client {
+    /// A client IP address.
+    Ip(IpAddr),
+    /// A bearer token.
+    Token(String),
+}
+
+/// A token bucket.
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// The number of tokens left, as of `updated_at`.
+    tokens: f64,
+    /// When the number of tokens has been updated.
+    updated_at: Instant,
+}
+
+/// The rate limiter of the clients.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    /// The token buckets of the clients.
+    buckets: HashMap<Client, Bucket>,
+}
+
+impl Clients {
+    /// Returns the clients of a request from an IP address, with an optional
+    /// bearer token.
+    pub fn new(ip: IpAddr, token: Option<&str>) -> Self {
+        let token = token.map(|token| Client::Token(token.to_owned()));
+        Self(
+            [Some(Client::Ip(ip)), token]
+                .into_iter()
+                .flatten()
+                .collect(),
+        )
+    }
+}
+
+impl RateLimiter {
+    /// Takes a token from the buckets of all the clients, if they all have
+    /// one left.
+    ///
+    /// Otherwise, returns the number of seconds to wait before retrying.
+    pub fn acquire(
+        &mut self,
+        clients: &Clients,
+        limits: Limits,
+        now: Instant,
+    ) -> Result<(), u64> {
+        let (rate, burst) = rate_and_burst(limits);
+
+        if self.buckets.len() > MAX_TRACKED_CLIENTS {
+            self.buckets.retain(|_, bucket| {
+                bucket.refill(now, rate, burst).tokens < burst
+            });
+        }
+
+        let buckets = self.refilled(clients, limits, now);
+
+        let missing = buckets
+            .iter()
+            .map(|bucket| 1.0_f64 - bucket.tokens)
+            .fold(0.0_f64, f64::max);
+        if missing > 0.0_f64 {
+            let wait = if rate > 0.0_f64 {
+                missing / rate
+            } else {
+                60.0_f64
+            };
+            return Err(Duration::from_secs_f64(wait.ceil()).as_secs());
+        }
+
+        self.take(clients, buckets, 1.0_f64, 0.0_f64);
+        Ok(())
+    }
+
+    /// Takes tokens from the buckets of all the clients, even if it leaves
+    /// them in debt, up to a minute of refill.
+    ///
+    /// This charges the notifications created in a batch beyond the first
+    /// one, whose token has already been taken by [`Self::acquire`].
+    pub fn charge(
+        &mut self,
+        clients: &Clients,
+        tokens: usize,
+        limits: Limits,
+        now: Instant,
+    ) {
+        if tokens == 0 {
+            return;
+        }
+
+        let tokens =
+            u32::try_from(tokens).map_or(f64::from(u32::MAX), f64::from);
+        let buckets = self.refilled(clients, limits, now);
+        self.take(clients, buckets, tokens, -f64::from(limits.rate));
+    }
+
+    /// Returns the buckets of the clients, refilled up to `now`.
+    fn refilled(
+        &self,
+        clients: &Clients,
+        limits: Limits,
+        now: Instant,
+    ) -> Vec<Bucket> {
+        let (rate, burst) = rate_and_burst(limits);
+
+        clients
+            .0
+            .iter()
+            .map(|client| {
+                self.buckets.get(client).map_or(
+                    Bucket {
+                        tokens: burst,
+                        updated_at: now,
+                    },
+                    |bucket| bucket.refill(now, rate, burst),
+                )
+            })
+            .collect()
+    }
+
+    /// Takes tokens from the buckets of the clients, leaving them with at
+    /// least `floor` tokens.
+    fn take(
+        &mut self,
+        clients: &Clients,
+        buckets: Vec<Bucket>,
+        tokens: f64,
+        floor: f64,
+    ) {
+        for (client, bucket) in clients.0.iter().zip(buckets) {
+            self.buckets.insert(
+                client.clone(),
+                Bucket {
+                    tokens: (bucket.tokens - tokens).max(floor),
+                    ..bucket
+                },
+            );
+        }
+    }
+}
+
+/// Returns the rate in tokens per second and the burst of the limits.
+fn rate_and_burst(limits: Limits) -> (f64, f64) {
+    (f64::from(limits.rate) / 60.0_f64, f64::from(limits.burst))
+}
+
+impl Bucket {
+    /// Returns the bucket refilled at `rate` tokens per second, up to `burst`.
+    fn refill(self, now: Instant, rate: f64, burst: f64) -> Self {
+        let elapsed = now.saturating_duration_since(self.updated_at);
+
+        Self {
+            tokens: elapsed.as_secs_f64().mul_add(rate, self.tokens).min(burst),
+            updated_at: now,
+        }
+    }
+}
+
+/// Rate limits the requests by client IP address and bearer token.
+///
+/// The clients are added to the extensions of the request, for the handlers
+/// creating notifications in batches to [`RateLimiter::charge`] them.
+pub async fn rate_limit(
+    State(state): State<Arc<Mutex<AppState>>>,
+    ConnectInfo(address): ConnectInfo<SocketAddr>,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, CreateNotificationError> {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|authorization| authorization.to_str().ok())
+        .and_then(|authorization| authorization.strip_prefix("Bearer "));
+    let clients = Clients::new(address.ip(), token);
+
+    {
+        let mut state = state.lock().expect("poisoned lock");
+        let limits = state.limits;
+        state
+            .rate_limiter
+            .acquire(&clients, limits, Instant::now())
+            .map_err(|retry_after| CreateNotificationError::RateLimited {
+                retry_after,
+            })
+            .log_err()?;
+    }
+
+    request.extensions_mut().insert(clients);
+    Ok(next.run(request).await)
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn acquire_limits_each_client() {
+        let limits = Limits {
+            rate: 60,
+            burst: 2,
+            ..Limits::default()
+        };
+        let mut rate_limiter = RateLimiter::default();
+        let now = Instant::now();
+        let ip = IpAddr::from([127, 0, 0, 1]);
+        let other_ip = IpAddr::from([127, 0, 0, 2]);
+        let both = Clients::new(ip, Some("secret"));
+
+        let mut acquire = |clients: &Clients, after: u64| {
+            rate_limiter.acquire(
+                clients,
+                limits,
+                now + Duration::from_millis(after),
+            )
+        };
+
+        assert_eq!(acquire(&both, 0), Ok(()));
+        assert_eq!(acquire(&both, 0), Ok(()));
+        assert_eq!(acquire(&Clients::new(ip, None), 0), Err(1));
+        // NOTE: The token is limited across IP addresses.
+        assert_eq!(acquire(&Clients::new(other_ip, Some("secret")), 0), Err(1));
+        assert_eq!(acquire(&Clients::new(other_ip, None), 0), Ok(()));
+        // NOTE: A token is refilled every second.
+        assert_eq!(acquire(&Clients::new(ip, None), 1000), Ok(()));
+        assert_eq!(acquire(&Clients::new(ip, None), 1000), Err(1));
+    }
+
+    #[test]
+    fn charge_leaves_the_buckets_in_debt() {
+        let limits = Limits {
+            rate: 60,
+            burst: 2,
+            ..Limits::default()
+        };
+        let mut rate_limiter = RateLimiter::default();
+        let now = Instant::now();
+        let clients = Clients::new(IpAddr::from([127, 0, 0, 1]), None);
+
+        assert_eq!(rate_limiter.acquire(&clients, limits, now), Ok(()));
+        rate_limiter.charge(&clients, 10, limits, now);
+        // NOTE: 1 token is left before, so 10 tokens are missing.
+        assert_eq!(rate_limiter.acquire(&clients, limits, now), Err(10));
+        assert_eq!(
+            rate_limiter.acquire(
+                &clients,
+                limits,
+                now + Duration::from_secs(10)
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn charge_caps_the_debt_to_a_minute() {
+        let limits = Limits {
+            rate: 60,
+            burst: 2,
+            ..Limits::default()
+        };
+        let mut rate_limiter = RateLimiter::default();
+        let now = Instant::now();
+        let clients = Clients::new(IpAddr::from([127, 0, 0, 1]), None);
+
+        rate_limiter.charge(&clients, 10_000, limits, now);
+        assert_eq!(rate_limiter.acquire(&clients, limits, now), Err(61));
+    }
+}
@@ -6,52 +6,336 @@
     reason = "that’s a PoC"
 )]
 
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use axum::{
     Router,
-    extract::{Path, State},
-    response::Json,
+    body::Bytes,
+    extract::{DefaultBodyLimit, Extension, Path, Query, State},
+    http::header,
+    middleware,
+    response::{
+        IntoResponse, Json,
+        sse::{Event, KeepAlive, Sse},
+    },
     routing::{delete, get, post, put},
 };
 use axum_extra::extract::WithRejection;
+use chrono::{DateTime, TimeDelta, Utc};
+use clap::Parser;
 use eyre::Result;
 use indexmap::IndexMap;
 use serde_json::{Value, json};
+use tokio::sync::broadcast;
+use tokio_stream::{
+    Stream, StreamExt as _,
+    wrappers::{BroadcastStream, errors::BroadcastStreamRecvError},
+};
 
 use ntf_api_types::{
-    CreateNotificationError, CreateNotificationPayload, Notification,
-    ResourceError,
+    Action, ActionKind, ActionOutcome, CreateNotificationError,
+    CreateNotificationPayload, ImportNotificationsResult, ListQuery,
+    Notification, NotificationEvent, ResourceError,
+    export::{self, ExportQuery},
 };
 use ntf_poc_helpers::tracing::LogResult as _;
 
+mod email;
+mod ingest;
+mod limits;
+mod outbound;
+mod validation;
+mod webhooks;
+
+/// The capacity of the event channel.
+const EVENT_CAPACITY: usize = 64;
+
+/// How long deleted notifications stay in the trash before being purged.
+const TRASH_RETENTION: TimeDelta = TimeDelta::days(7);
+
+/// The interval between two purges of the trash.
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// A web service for notifications.
+#[derive(Debug, Parser)]
+#[command(author, version)]
+struct Args {
+    /// The options of the email gateway.
+    #[command(flatten)]
+    email: email::EmailArgs,
+    /// The options of the inbound adapters.
+    #[command(flatten)]
+    ingest: ingest::IngestArgs,
+    /// The limits of the service.
+    #[command(flatten)]
+    limits: limits::Limits,
+    /// The options of the outgoing HTTP requests.
+    #[command(flatten)]
+    outbound: outbound::OutboundArgs,
+}
+
 /// The state of the web service.
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct AppState {
-    /// The notifications.
+    /// The notifications, including the ones in the trash.
     pub notifications: IndexMap<usize, Notification>,
+    /// The ID of the last created notification, never reused.
+    pub last_id: usize,
+    /// The sender for notification events.
+    pub events: broadcast::Sender<NotificationEvent>,
+    /// The HTTP client to call webhooks.
+    pub http: outbound::HttpClient,
+    /// The notifications created from inbound alerts, by alert key.
+    pub alerts: HashMap<String, usize>,
+    /// The secret GitHub-style webhooks are signed with.
+    pub github_secret: Option<String>,
+    /// The limits of the service.
+    pub limits: limits::Limits,
+    /// The rate limiter of the routes creating notifications.
+    pub rate_limiter: limits::RateLimiter,
+    /// The outgoing webhooks, called on notification events.
+    pub webhooks: webhooks::Webhooks,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
     tracing_subscriber::fmt().init();
+    let args = Args::parse();
+
+    let state = Arc::new(Mutex::new(AppState {
+        limits: args.limits,
+        http: outbound::HttpClient::new(args.outbound.allowed_hosts),
+        github_secret: args.ingest.github_secret,
+        ..AppState::default()
+    }));
+    tokio::spawn(purge_trash(Arc::clone(&state)));
+    tokio::spawn(webhooks::run(Arc::clone(&state)));
+
+    if let Some(mailer) = email::Mailer::new(&args.email)? {
+        tokio::spawn(email::send_emails(Arc::clone(&state), mailer));
+    }
+
+    if let Some(address) = args.email.smtp_listen {
+        let listener = tokio::net::TcpListener::bind(address).await?;
+        tokio::spawn(email::receive_emails(Arc::clone(&state), listener));
+    }
 
-    let state = Arc::new(Mutex::new(AppState::default()));
+    let app = router(state);
 
-    let app = Router::new()
+    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Builds the router of the service.
+fn router(state: Arc<Mutex<AppState>>) -> Router {
+    let limits = state.lock().expect("poisoned lock").limits;
+
+    // NOTE: Only the routes creating notifications or calling webhooks are rate
+    // limited.
+    let rate_limited = Router::new()
+        .route("/notifications", post(create_notification))
+        .route("/notifications/{id}/actions/{name}", post(run_action))
+        .route(
+            "/import",
+            post(import_notifications)
+                .layer(DefaultBodyLimit::max(limits.max_import_size)),
+        )
+        .merge(ingest::routes())
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            limits::rate_limit,
+        ));
+
+    Router::new()
         .route("/status", get(status))
         .route("/notifications", get(list_notifications))
-        .route("/notifications", post(create_notification))
         .route("/notifications/{id}", get(get_notification))
         .route("/notifications/{id}", put(ack_notification))
         .route("/notifications/{id}", delete(delete_notification))
-        .with_state(state);
+        .route("/notifications/{id}/restore", post(restore_notification))
+        .route("/trash", get(list_trash))
+        .route("/events", get(events))
+        .route("/export", get(export_notifications))
+        .merge(rate_limited)
+        .merge(webhooks::routes())
+        .layer(DefaultBodyLimit::max(limits.max_body_size))
+        .with_state(state)
+}
 
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
-    axum::serve(listener, app).await?;
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            notifications: IndexMap::new(),
+            last_id: 0,
+            events: broadcast::Sender::new(EVENT_CAPACITY),
+            http: outbound::HttpClient::default(),
+            alerts: HashMap::new(),
+            github_secret: None,
+            limits: limits::Limits::default(),
+            rate_limiter: limits::RateLimiter::default(),
+            webhooks: webhooks::Webhooks::default(),
+        }
+    }
+}
 
-    Ok(())
+impl AppState {
+    /// Returns the ID for the next notification.
+    ///
+    /// IDs are never reused, even once their notification has been purged or
+    /// evicted, as clients may still refer to it.
+    fn next_id(&mut self) -> usize {
+        self.last_id += 1;
+        self.last_id
+    }
+
+    /// Returns the notifications, excluding or only including the ones in the
+    /// trash.
+    fn notifications(
+        &self,
+        deleted: bool,
+    ) -> impl Iterator<Item = &Notification> {
+        self.notifications
+            .values()
+            .filter(move |ntf| ntf.deleted_at.is_some() == deleted)
+    }
+
+    /// Returns a notification not in the trash.
+    fn live(&self, id: usize) -> Option<&Notification> {
+        self.notifications
+            .get(&id)
+            .filter(|ntf| ntf.deleted_at.is_none())
+    }
+
+    /// Returns a mutable reference to a notification not in the trash.
+    fn live_mut(&mut self, id: usize) -> Option<&mut Notification> {
+        self.notifications
+            .get_mut(&id)
+            .filter(|ntf| ntf.deleted_at.is_none())
+    }
+
+    /// Removes the notifications deleted before `deleted_before`.
+    ///
+    /// Returns the number of purged notifications.
+    fn purge(&mut self, deleted_before: DateTime<Utc>) -> usize {
+        let count = self.notifications.len();
+        self.notifications.retain(|_, ntf| {
+            ntf.deleted_at
+                .is_none_or(|deleted_at| deleted_at >= deleted_before)
+        });
+        self.prune_alerts();
+        count - self.notifications.len()
+    }
+
+    /// Forgets the alerts whose notification has been removed.
+    fn prune_alerts(&mut self) {
+        let notifications = &self.notifications;
+        self.alerts.retain(|_, id| notifications.contains_key(id));
+    }
+
+    /// Creates a notification from a validated payload.
+    fn create(&mut self, payload: CreateNotificationPayload) -> Notification {
+        let id = self.next_id();
+        let notification = Notification {
+            id,
+            message: payload.message,
+            format: payload.format,
+            ack: false,
+            tags: payload.tags,
+            priority: payload.priority,
+            created_at: Utc::now(),
+            acked_at: None,
+            deleted_at: None,
+            actions: payload
+                .actions
+                .into_iter()
+                .map(|action| Action {
+                    outcome: None,
+                    ..action
+                })
+                .collect(),
+        };
+        self.notifications.insert(id, notification.clone());
+        self.publish(NotificationEvent::Created(notification.clone()));
+        self.evict();
+
+        notification
+    }
+
+    /// Evicts the notifications beyond the maximum number, starting with the
+    /// ones in the trash, then the acknowledged ones, then the oldest ones.
+    fn evict(&mut self) {
+        self.make_room(0);
+    }
+
+    /// Evicts notifications so that `count` more can be added without going
+    /// beyond the maximum number, in the same order as [`Self::evict`].
+    fn make_room(&mut self, count: usize) {
+        let count = (self.notifications.len() + count)
+            .saturating_sub(self.limits.max_notifications);
+        if count == 0 {
+            return;
+        }
+
+        let evicted = self
+            .notifications(true)
+            .chain(self.notifications(false).filter(|ntf| ntf.ack))
+            .chain(self.notifications(false).filter(|ntf| !ntf.ack))
+            .map(|ntf| ntf.id)
+            .take(count)
+            .collect::<HashSet<_>>();
+        self.notifications.retain(|id, _| !evicted.contains(id));
+        self.prune_alerts();
+
+        tracing::info!(count, "EVICT");
+    }
+
+    /// Acknowledges a notification not in the trash, if not already.
+    fn ack(&mut self, id: usize) -> Option<Notification> {
+        let notification = self.live_mut(id)?;
+        let newly_acked = !notification.ack;
+        if newly_acked {
+            notification.ack = true;
+            notification.acked_at = Some(Utc::now());
+        }
+
+        let notification = notification.clone();
+        if newly_acked {
+            self.publish(NotificationEvent::Acked(notification.clone()));
+        }
+
+        Some(notification)
+    }
+
+    /// Charges the clients for the notifications created in a batch, beyond
+    /// the first one charged with the request.
+    fn charge(&mut self, clients: &limits::Clients, count: usize) {
+        let limits = self.limits;
+        self.rate_limiter.charge(
+            clients,
+            count.saturating_sub(1),
+            limits,
+            Instant::now(),
+        );
+    }
+
+    /// Publishes an event to the subscribers.
+    fn publish(&self, event: NotificationEvent) {
+        if self.events.send(event).is_err() {
+            tracing::trace!("no subscriber for the event");
+        }
+    }
 }
 
 /// Returns the status.
@@ -63,12 +347,14 @@ async fn status() -> Json<Value> {
 #[tracing::instrument(skip(state))]
 async fn list_notifications(
     State(state): State<Arc<Mutex<AppState>>>,
+    Query(query): Query<ListQuery>,
 ) -> Json<Vec<Notification>> {
     let notifications = state
         .lock()
         .expect("poisoned lock")
-        .notifications
-        .values()
+        .notifications(false)
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
         .cloned()
         .collect();
 
@@ -76,23 +362,53 @@ async fn list_notifications(
     Json(notifications)
 }
 
+/// Lists the notifications in the trash.
+#[tracing::instrument(skip(state))]
+async fn list_trash(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Query(query): Query<ListQuery>,
+) -> Json<Vec<Notification>> {
+    let notifications = state
+        .lock()
+        .expect("poisoned lock")
+        .notifications(true)
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect();
+
+    tracing::info!(?notifications, "TRASH");
+    Json(notifications)
+}
+
+/// Returns whether a URL is an HTTP or HTTPS one.
+fn is_http_url(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
 /// Gets a notification by its ID.
 #[tracing::instrument(skip(state))]
 async fn create_notification(
     State(state): State<Arc<Mutex<AppState>>>,
     WithRejection(Json(payload), _): WithRejection<
-        Json<CreateNotificationPayload>,
+        Json<Value>,
         CreateNotificationError,
     >,
-) -> Result<Notification, ResourceError> {
+) -> Result<Notification, CreateNotificationError> {
+    let payload: CreateNotificationPayload =
+        validation::deserialize(payload).log_err()?;
+
     let mut state = state.lock().expect("poisoned lock");
-    let id = state.notifications.keys().last().unwrap_or(&0) + 1;
-    let notification = Notification {
-        id,
-        message: payload.message,
-        ack: false,
-    };
-    state.notifications.insert(id, notification.clone());
+    let mut validator = validation::Validator::default();
+    validator.message(
+        "message",
+        &payload.message,
+        state.limits.max_message_length,
+    );
+    validator.actions("actions", &payload.actions);
+    validator.finish().log_err()?;
+
+    let notification = state.create(payload);
 
     tracing::info!(?notification, "CREATE");
     Ok(notification)
@@ -107,8 +423,7 @@ async fn get_notification(
     let notification = state
         .lock()
         .expect("poisoned lock")
-        .notifications
-        .get(&id)
+        .live(id)
         .cloned()
         .ok_or(ResourceError::NotFound { id })
         .log_err()?;
@@ -123,31 +438,397 @@ async fn ack_notification(
     State(state): State<Arc<Mutex<AppState>>>,
     Path(id): Path<usize>,
 ) -> Result<Notification, ResourceError> {
-    let mut state = state.lock().expect("poisoned lock");
-    match state.notifications.get_mut(&id) {
-        Some(notification) => {
-            notification.ack = true;
-            tracing::info!(?notification, "ACK");
-            Ok(notification.clone())
-        }
-        None => Err(ResourceError::NotFound { id }).log_err(),
-    }
+    let notification = state
+        .lock()
+        .expect("poisoned lock")
+        .ack(id)
+        .ok_or(ResourceError::NotFound { id })
+        .log_err()?;
+
+    tracing::info!(?notification, "ACK");
+    Ok(notification)
 }
 
-/// Delete a notification.
+/// Moves a notification to the trash.
 #[tracing::instrument(skip(state))]
 async fn delete_notification(
     State(state): State<Arc<Mutex<AppState>>>,
     Path(id): Path<usize>,
 ) -> Result<Notification, ResourceError> {
+    let mut state = state.lock().expect("poisoned lock");
     let notification = state
-        .lock()
-        .expect("poisoned lock")
-        .notifications
-        .shift_remove(&id)
+        .live_mut(id)
         .ok_or(ResourceError::NotFound { id })
         .log_err()?;
+    notification.deleted_at = Some(Utc::now());
+
+    let notification = notification.clone();
+    state.publish(NotificationEvent::Deleted(notification.clone()));
 
     tracing::info!(?notification, "DELETE");
     Ok(notification)
 }
+
+/// Restores a notification from the trash.
+#[tracing::instrument(skip(state))]
+async fn restore_notification(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path(id): Path<usize>,
+) -> Result<Notification, ResourceError> {
+    let mut state = state.lock().expect("poisoned lock");
+    let notification = state
+        .notifications
+        .get_mut(&id)
+        .filter(|ntf| ntf.deleted_at.is_some())
+        .ok_or(ResourceError::NotFound { id })
+        .log_err()?;
+    notification.deleted_at = None;
+
+    let notification = notification.clone();
+    state.publish(NotificationEvent::Restored(notification.clone()));
+
+    tracing::info!(?notification, "RESTORE");
+    Ok(notification)
+}
+
+/// Runs an action of a notification, and records its outcome.
+///
+/// Webhooks are called by the service. URL actions are opened by the clients,
+/// so running them only records it.
+#[tracing::instrument(skip(state))]
+async fn run_action(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path((id, name)): Path<(usize, String)>,
+) -> Result<Notification, ResourceError> {
+    let (kind, http) = {
+        let state = state.lock().expect("poisoned lock");
+        let action = state
+            .live(id)
+            .ok_or(ResourceError::NotFound { id })
+            .log_err()?
+            .actions
+            .iter()
+            .find(|action| action.name == name)
+            .ok_or_else(|| ResourceError::ActionNotFound {
+                id,
+                name: name.clone(),
+            })
+            .log_err()?;
+
+        (action.kind.clone(), state.http.clone())
+    };
+
+    let outcome = match kind {
+        ActionKind::Url { .. } => ActionOutcome {
+            run_at: Utc::now(),
+            status: None,
+            error: None,
+        },
+        ActionKind::Webhook { url, body } => {
+            call_webhook(&http, &url, body.unwrap_or_default()).await
+        }
+    };
+
+    // NOTE: The notification may have been deleted while calling the webhook.
+    let mut state = state.lock().expect("poisoned lock");
+    let notification = state
+        .live_mut(id)
+        .ok_or(ResourceError::NotFound { id })
+        .log_err()?;
+
+    if let Some(action) = notification
+        .actions
+        .iter_mut()
+        .find(|action| action.name == name)
+    {
+        action.outcome = Some(outcome);
+    }
+
+    tracing::info!(?notification, name, "ACTION");
+    Ok(notification.clone())
+}
+
+/// Calls a webhook with `POST`, and returns the outcome.
+async fn call_webhook(
+    http: &outbound::HttpClient,
+    url: &str,
+    body: String,
+) -> ActionOutcome {
+    let run_at = Utc::now();
+    let result = match http.post(url) {
+        Ok(request) => request
+            .body(body)
+            .send()
+            .await
+            .map_err(|error| error.to_string()),
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(response) => ActionOutcome {
+            run_at,
+            status: Some(response.status().as_u16()),
+            error: None,
+        },
+        Err(error) => {
+            tracing::warn!(%error, url, "failed to call the webhook");
+            ActionOutcome {
+                run_at,
+                status: None,
+                error: Some(error),
+            }
+        }
+    }
+}
+
+/// Purges the trash periodically from the notifications deleted for longer
+/// than [`TRASH_RETENTION`].
+#[expect(clippy::infinite_loop, reason = "runs as long as the service")]
+async fn purge_trash(state: Arc<Mutex<AppState>>) {
+    let mut interval = tokio::time::interval(PURGE_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let count = state
+            .lock()
+            .expect("poisoned lock")
+            .purge(Utc::now() - TRASH_RETENTION);
+
+        if count > 0 {
+            tracing::info!(count, "PURGE");
+        }
+    }
+}
+
+/// Exports all the notifications in the requested format.
+#[tracing::instrument(skip(state))]
+async fn export_notifications(
+    State(state): State<Arc<Mutex<AppState>>>,
+    WithRejection(Query(query), _): WithRejection<
+        Query<ExportQuery>,
+        CreateNotificationError,
+    >,
+) -> Result<impl IntoResponse, CreateNotificationError> {
+    let notifications = state
+        .lock()
+        .expect("poisoned lock")
+        .notifications(false)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let mut body = Vec::new();
+    export::write(query.format, &notifications, &mut body)
+        .expect("failed to export to memory");
+
+    tracing::info!(count = notifications.len(), "EXPORT");
+    Ok(([(header::CONTENT_TYPE, query.format.content_type())], body))
+}
+
+/// Imports notifications, preserving their acknowledgement state and
+/// timestamps.
+///
+/// The imported notifications are given new IDs, and their actions are reset
+/// as never run. Existing notifications are evicted to make room for them, and
+/// imports larger than the maximum number of notifications are rejected.
+#[tracing::instrument(skip(state, clients, body))]
+async fn import_notifications(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Extension(clients): Extension<limits::Clients>,
+    WithRejection(Query(query), _): WithRejection<
+        Query<ExportQuery>,
+        CreateNotificationError,
+    >,
+    WithRejection(body, _): WithRejection<Bytes, CreateNotificationError>,
+) -> Result<Json<ImportNotificationsResult>, CreateNotificationError> {
+    let notifications = export::read(query.format, body.as_ref())
+        .map_err(|error| {
+            CreateNotificationError::PayloadError(error.to_string())
+        })
+        .log_err()?;
+
+    let mut state = state.lock().expect("poisoned lock");
+    let mut validator = validation::Validator::default();
+    for (index, notification) in notifications.iter().enumerate() {
+        validator.message(
+            &format!("[{index}].message"),
+            &notification.message,
+            state.limits.max_message_length,
+        );
+        validator.actions(&format!("[{index}].actions"), &notification.actions);
+    }
+    validator.finish().log_err()?;
+
+    if notifications.len() > state.limits.max_notifications {
+        return Err(CreateNotificationError::PayloadError(format!(
+            "cannot import {} notifications, at most {} are kept",
+            notifications.len(),
+            state.limits.max_notifications
+        )))
+        .log_err();
+    }
+
+    // NOTE: Make room beforehand so that no imported notification is evicted.
+    state.make_room(notifications.len());
+    let mut imported = Vec::with_capacity(notifications.len());
+
+    for notification in notifications {
+        let id = state.next_id();
+        let notification = Notification {
+            id,
+            actions: notification
+                .actions
+                .into_iter()
+                .map(|action| Action {
+                    outcome: None,
+                    ..action
+                })
+                .collect(),
+            ..notification
+        };
+        state.notifications.insert(id, notification.clone());
+        state.publish(NotificationEvent::Created(notification.clone()));
+        imported.push(notification);
+    }
+    state.charge(&clients, imported.len());
+
+    tracing::info!(count = imported.len(), "IMPORT");
+    Ok(Json(ImportNotificationsResult::Notifications(imported)))
+}
+
+/// Streams the notification events as server-sent events.
+#[tracing::instrument(skip(state))]
+async fn events(
+    State(state): State<Arc<Mutex<AppState>>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    let receiver = state.lock().expect("poisoned lock").events.subscribe();
+
+    tracing::info!("EVENTS");
+    let stream =
+        BroadcastStream::new(receiver).filter_map(|event| match event {
+            Ok(event) => Some(Event::default().json_data(event)),
+            Err(BroadcastStreamRecvError::Lagged(count)) => {
+                tracing::warn!(count, "some events have been dropped");
+                None
+            }
+        });
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use std::fmt::Write as _;
+
+    use super::*;
+
+    async fn serve(state: Arc<Mutex<AppState>>) -> String {
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let app =
+            router(state).into_make_service_with_connect_info::<SocketAddr>();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        format!("http://{address}")
+    }
+
+    fn export(count: usize) -> String {
+        let message = "x".repeat(100);
+        let mut export = String::new();
+
+        for id in 1..=count {
+            writeln!(
+                export,
+                "{{\"id\":{id},\"message\":\"{message}\",\"ack\":false}}"
+            )
+            .unwrap();
+        }
+
+        export
+    }
+
+    async fn import(base_url: &str, body: String) -> reqwest::Response {
+        reqwest::Client::new()
+            .post(format!("{base_url}/import?format=ndjson"))
+            .body(body)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn ids_are_not_reused() {
+        let mut state = AppState::default();
+        let payload = || CreateNotificationPayload {
+            message: String::from("hello"),
+            format: ntf_api_types::Format::default(),
+            tags: Vec::new(),
+            priority: ntf_api_types::Priority::default(),
+            actions: Vec::new(),
+        };
+
+        let id = state.create(payload()).id;
+        state.notifications.clear();
+
+        assert_eq!(state.create(payload()).id, id + 1);
+    }
+
+    #[tokio::test]
+    async fn unknown_export_formats_are_rejected_as_json() {
+        let base_url = serve(Arc::new(Mutex::new(AppState::default()))).await;
+
+        let response = reqwest::get(format!("{base_url}/export?format=xml"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+
+        let body =
+            serde_json::from_slice::<Value>(&response.bytes().await.unwrap())
+                .unwrap();
+        assert!(body.get("error").is_some(), "{body}");
+    }
+
+    #[tokio::test]
+    async fn import_accepts_more_than_the_body_limit() {
+        let state = Arc::new(Mutex::new(AppState::default()));
+        let base_url = serve(Arc::clone(&state)).await;
+
+        let body = export(1000);
+        assert!(body.len() > limits::Limits::default().max_body_size);
+
+        let response = import(&base_url, body).await;
+        assert_eq!(response.status(), 200);
+        assert_eq!(state.lock().unwrap().notifications.len(), 1000);
+    }
+
+    #[tokio::test]
+    async fn import_makes_room_or_is_rejected() {
+        let state = Arc::new(Mutex::new(AppState {
+            limits: limits::Limits {
+                max_notifications: 10,
+                ..limits::Limits::default()
+            },
+            ..AppState::default()
+        }));
+        let base_url = serve(Arc::clone(&state)).await;
+
+        let response = import(&base_url, export(11)).await;
+        assert_eq!(response.status(), 400);
+        assert!(state.lock().unwrap().notifications.is_empty());
+
+        assert_eq!(import(&base_url, export(8)).await.status(), 200);
+        assert_eq!(import(&base_url, export(5)).await.status(), 200);
+
+        let ids = state
+            .lock()
+            .unwrap()
+            .notifications
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(ids, (4..=13).collect::<Vec<_>>());
+    }
+}
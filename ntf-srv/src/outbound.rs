@@ -0,0 +1,250 @@
+//! The HTTP client calling the webhooks, guarded against server-side request
+//! forgery.
+//!
+//! The URLs of the webhooks are given by the clients, so the service:
+//!
+//! - only calls the hosts in the allowlist, when one is configured,
+//! - never calls loopback, private or link-local addresses, unless their host
+//!   is explicitly in the allowlist,
+//! - never follows redirects.
+//!
+//! Domain names are checked on resolution, so that they cannot be pointed to a
+//! private address after the URL has been checked.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
+
+use clap::Args;
+use reqwest::{
+    RequestBuilder, Url,
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect,
+};
+
+/// The maximum time to wait for a webhook to respond.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The options of the outgoing HTTP requests.
+#[derive(Debug, Clone, Args)]
+pub struct OutboundArgs {
+    /// The hosts the webhooks can be called on, comma-separated.
+    ///
+    /// All the public hosts are allowed when empty. Loopback, private and
+    /// link-local addresses are only called when their host is in the list.
+    #[arg(
+        long = "webhook-allowed-hosts",
+        env = "NTF_WEBHOOK_ALLOWED_HOSTS",
+        value_name = "HOSTS",
+        value_delimiter = ','
+    )]
+    pub allowed_hosts: Vec<String>,
+}
+
+/// The HTTP client calling the webhooks.
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    /// The underlying client.
+    client: reqwest::Client,
+    /// The hosts the webhooks can be called on.
+    allowed_hosts: AllowedHosts,
+}
+
+/// The hosts the webhooks can be called on, all public ones when empty.
+#[derive(Debug, Clone, Default)]
+struct AllowedHosts(Arc<[String]>);
+
+/// A DNS resolver dropping the non-public addresses of the hosts not allowed
+/// explicitly.
+#[derive(Debug)]
+struct Resolver {
+    /// The hosts the webhooks can be called on.
+    allowed_hosts: AllowedHosts,
+}
+
+impl HttpClient {
+    /// Builds a client calling the given hosts only, or all the public ones
+    /// when empty.
+    pub fn new(allowed_hosts: Vec<String>) -> Self {
+        let allowed_hosts = AllowedHosts(allowed_hosts.into());
+        let client = reqwest::Client::builder()
+            .redirect(redirect::Policy::none())
+            .timeout(WEBHOOK_TIMEOUT)
+            .dns_resolver(Arc::new(Resolver {
+                allowed_hosts: allowed_hosts.clone(),
+            }))
+            .build()
+            .expect("the HTTP client configuration is valid");
+
+        Self {
+            client,
+            allowed_hosts,
+        }
+    }
+
+    /// Starts a `POST` request to a URL, if the URL is allowed.
+    pub fn post(&self, url: &str) -> Result<RequestBuilder, String> {
+        let url = Url::parse(url).map_err(|error| error.to_string())?;
+        self.check(&url)?;
+        Ok(self.client.post(url))
+    }
+
+    /// Checks that a URL can be called.
+    fn check(&self, url: &Url) -> Result<(), String> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("unsupported scheme `{}`", url.scheme()));
+        }
+
+        let host = url.host_str().ok_or("missing host")?;
+        let explicitly_allowed = self.allowed_hosts.contains(host);
+
+        if !explicitly_allowed && !self.allowed_hosts.is_empty() {
+            return Err(format!("host `{host}` is not allowed"));
+        }
+
+        // NOTE: Domain names are checked by the resolver.
+        let ip = host.trim_start_matches('[').trim_end_matches(']').parse();
+        match ip {
+            Ok(ip) if !explicitly_allowed && !is_public(ip) => {
+                Err(format!("address `{ip}` is not public"))
+            }
+            Ok(_) | Err(_) => Ok(()),
+        }
+    }
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl AllowedHosts {
+    /// Returns whether no host is allowed explicitly.
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns whether a host is allowed explicitly.
+    fn contains(&self, host: &str) -> bool {
+        self.0
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    }
+}
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let explicitly_allowed = self.allowed_hosts.contains(name.as_str());
+
+        Box::pin(async move {
+            let addresses = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|address| explicitly_allowed || is_public(address.ip()))
+                .collect::<Vec<SocketAddr>>();
+
+            if addresses.is_empty() {
+                return Err(format!(
+                    "`{}` has no public address",
+                    name.as_str()
+                )
+                .into());
+            }
+
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            Ok(addresses)
+        })
+    }
+}
+
+/// Returns whether an IP address is a public one.
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or_else(
+            || {
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            },
+            is_public_v4,
+        ),
+    }
+}
+
+/// Returns whether an IPv4 address is a public one.
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, ..] = ip.octets();
+    // NOTE: 0.0.0.0/8 is “this network” and 100.64.0.0/10 is shared by
+    // carrier-grade NATs.
+    let reserved = first == 0 || (first == 100 && second & 0xc0 == 64);
+
+    !(reserved
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation())
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use super::*;
+
+    #[test]
+    fn is_public_rejects_internal_addresses() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "0.0.0.0",
+            "100.64.0.1",
+            "::1",
+            "fc00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip} is internal");
+        }
+
+        for ip in ["93.184.215.14", "2606:4700::1111"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip} is public");
+        }
+    }
+
+    #[test]
+    fn post_checks_the_host() {
+        let client = HttpClient::default();
+        let check = |url| client.post(url).map(drop);
+        assert_eq!(check("https://example.com/hook"), Ok(()));
+        assert_eq!(
+            check("ftp://example.com/hook"),
+            Err(String::from("unsupported scheme `ftp`"))
+        );
+        assert_eq!(
+            check("http://169.254.169.254/"),
+            Err(String::from("address `169.254.169.254` is not public"))
+        );
+        assert_eq!(
+            check("http://[::1]:8080/"),
+            Err(String::from("address `::1` is not public"))
+        );
+
+        let client = HttpClient::new(vec![String::from("127.0.0.1")]);
+        let check = |url| client.post(url).map(drop);
+        assert_eq!(check("http://127.0.0.1:8080/"), Ok(()));
+        assert_eq!(
+            check("https://example.com/"),
+            Err(String::from("host `example.com` is not allowed"))
+        );
+    }
+}
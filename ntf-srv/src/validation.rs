@@ -0,0 +1,246 @@
+//! The validation of the payloads, reporting the invalid fields.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use ntf_api_types::{
+    Action, ActionKind, CreateNotificationError, ValidationError,
+    ValidationErrorCode,
+};
+
+use crate::is_http_url;
+
+/// A collector of validation errors.
+#[derive(Debug, Default)]
+pub struct Validator {
+    /// The errors found so far.
+    errors: Vec<ValidationError>,
+}
+
+impl Validator {
+    /// Checks that a message is neither empty nor too long.
+    pub fn message(&mut self, field: &str, message: &str, max_length: usize) {
+        let length = message.chars().count();
+
+        if message.trim().is_empty() {
+            self.error(
+                field,
+                ValidationErrorCode::Empty,
+                "the message is empty",
+            );
+        } else if length > max_length {
+            self.error(
+                field,
+                ValidationErrorCode::TooLong,
+                format!(
+                    "the message is too long ({length} characters, \
+                        at most {max_length})"
+                ),
+            );
+        } else {
+            // NOTE: The message is valid.
+        }
+    }
+
+    /// Checks that the actions have unique names usable in a URL, and HTTP
+    /// URLs.
+    pub fn actions(&mut self, field: &str, actions: &[Action]) {
+        for (index, action) in actions.iter().enumerate() {
+            let name = &action.name;
+            let url = match &action.kind {
+                ActionKind::Url { url } | ActionKind::Webhook { url, .. } => {
+                    url
+                }
+            };
+
+            if name.is_empty()
+                || !name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "-_".contains(c))
+            {
+                self.error(
+                    &format!("{field}[{index}].name"),
+                    ValidationErrorCode::InvalidValue,
+                    format!(
+                        "invalid action name `{name}` \
+                            (expected letters, digits, - or _)"
+                    ),
+                );
+            } else if actions
+                .iter()
+                .take(index)
+                .any(|other| other.name == *name)
+            {
+                self.error(
+                    &format!("{field}[{index}].name"),
+                    ValidationErrorCode::Duplicate,
+                    format!("duplicate action name `{name}`"),
+                );
+            } else {
+                // NOTE: The name is valid.
+            }
+
+            self.url(&format!("{field}[{index}].url"), url);
+        }
+    }
+
+    /// Checks that a URL is an HTTP or HTTPS one.
+    pub fn url(&mut self, field: &str, url: &str) {
+        if !is_http_url(url) {
+            self.error(
+                field,
+                ValidationErrorCode::InvalidValue,
+                format!("invalid URL `{url}` (expected http or https)"),
+            );
+        }
+    }
+
+    /// Returns the errors found, if any.
+    pub fn finish(self) -> Result<(), CreateNotificationError> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(CreateNotificationError::ValidationErrors(self.errors))
+        }
+    }
+
+    /// Adds an error.
+    fn error(
+        &mut self,
+        field: &str,
+        code: ValidationErrorCode,
+        message: impl Into<String>,
+    ) {
+        self.errors.push(ValidationError::new(field, code, message));
+    }
+}
+
+/// Deserialises a payload, reporting the first invalid field.
+pub fn deserialize<T: DeserializeOwned>(
+    value: Value,
+) -> Result<T, CreateNotificationError> {
+    serde_path_to_error::deserialize(value).map_err(|error| {
+        let path = error.path().to_string();
+        let message = error.into_inner().to_string();
+
+        // NOTE: serde only reports the kind of error in its message.
+        let (field, code) = if let Some(name) = missing_field(&message) {
+            let field = if path == "." {
+                name.to_owned()
+            } else {
+                format!("{path}.{name}")
+            };
+            (field, ValidationErrorCode::Missing)
+        } else if message.starts_with("invalid type") {
+            (path, ValidationErrorCode::InvalidType)
+        } else {
+            (path, ValidationErrorCode::InvalidValue)
+        };
+
+        CreateNotificationError::ValidationErrors(vec![ValidationError::new(
+            field, code, message,
+        )])
+    })
+}
+
+/// Returns the name of the missing field, from a serde error message.
+fn missing_field(message: &str) -> Option<&str> {
+    message
+        .strip_prefix("missing field `")?
+        .split_once('`')
+        .map(|(name, _)| name)
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use serde_json::json;
+
+    use ntf_api_types::CreateNotificationPayload;
+
+    use super::*;
+
+    fn errors(
+        result: Result<(), CreateNotificationError>,
+    ) -> Vec<(String, ValidationErrorCode)> {
+        match result {
+            Err(CreateNotificationError::ValidationErrors(errors)) => errors
+                .into_iter()
+                .map(|error| (error.field, error.code))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn validator_reports_all_invalid_fields() {
+        let payload = deserialize::<CreateNotificationPayload>(json!({
+            "message": "  ",
+            "actions": [
+                {"name": "open", "label": "Open", "type": "url", "url": "ftp://x"},
+                {"name": "open", "label": "Open", "type": "url", "url": "http://x"},
+            ],
+        }))
+        .unwrap();
+
+        let mut validator = Validator::default();
+        validator.message("message", &payload.message, 10);
+        validator.actions("actions", &payload.actions);
+
+        assert_eq!(
+            errors(validator.finish()),
+            [
+                (String::from("message"), ValidationErrorCode::Empty),
+                (
+                    String::from("actions[0].url"),
+                    ValidationErrorCode::InvalidValue
+                ),
+                (
+                    String::from("actions[1].name"),
+                    ValidationErrorCode::Duplicate
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn validator_rejects_long_messages() {
+        let mut validator = Validator::default();
+        validator.message("message", "héllo", 5);
+        assert!(validator.finish().is_ok(), "5 characters");
+
+        let mut validator = Validator::default();
+        validator.message("message", "hello!", 5);
+        assert_eq!(
+            errors(validator.finish()),
+            [(String::from("message"), ValidationErrorCode::TooLong)]
+        );
+    }
+
+    #[test]
+    fn deserialize_reports_the_invalid_field() {
+        let result = deserialize::<CreateNotificationPayload>(json!({
+            "message": "Disk full",
+            "priority": "critical",
+        }));
+        assert_eq!(
+            errors(result.map(drop)),
+            [(String::from("priority"), ValidationErrorCode::InvalidValue)]
+        );
+
+        let result = deserialize::<CreateNotificationPayload>(json!({}));
+        assert_eq!(
+            errors(result.map(drop)),
+            [(String::from("message"), ValidationErrorCode::Missing)]
+        );
+
+        let result = deserialize::<CreateNotificationPayload>(json!({
+            "message": 42_i32,
+        }));
+        assert_eq!(
+            errors(result.map(drop)),
+            [(String::from("message"), ValidationErrorCode::InvalidType)]
+        );
+    }
+}
@@ -0,0 +1,619 @@
+//! Outgoing webhooks, called on notification events.
+//!
+//! Each event is sent with `POST` to the matching webhooks, as the JSON of the
+//! [`NotificationEvent`]. The kind of the event is given in the `X-Ntf-Event`
+//! header. When the webhook has a secret, the body is signed with HMAC-SHA256
+//! and the signature is given in the `X-Ntf-Signature` header, as
+//! `sha256=<hex>`.
+//!
+//! The deliveries go through a bounded background queue, and are attempted
+//! concurrently so that a slow webhook does not delay the others. Failed ones
+//! are retried with an exponential backoff, then moved to the dead letters.
+
+use std::{
+    collections::VecDeque,
+    fmt::Write as _,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::Json,
+    routing::get,
+};
+use axum_extra::extract::WithRejection;
+use chrono::Utc;
+use hmac::{Hmac, Mac as _};
+use indexmap::IndexMap;
+use sha2::Sha256;
+use tokio::sync::{
+    OwnedSemaphorePermit, Semaphore, broadcast::error::RecvError, mpsc,
+};
+
+use ntf_api_types::{
+    CreateNotificationError, DeadLetter, EventKind, NotificationEvent,
+    ResourceError, Webhook, WebhookPayload,
+};
+use ntf_poc_helpers::tracing::LogResult as _;
+
+use crate::{AppState, outbound::HttpClient, validation::Validator};
+
+/// The maximum number of attempts to deliver an event.
+const MAX_ATTEMPTS: u32 = 5;
+
+/// The delay before the first retry, doubled on each attempt.
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// The capacity of the delivery queue.
+const QUEUE_CAPACITY: usize = 1024;
+
+/// The maximum number of delivery attempts in progress.
+const MAX_CONCURRENT_ATTEMPTS: usize = 16;
+
+/// The maximum number of dead letters, above which the oldest ones are
+/// dropped.
+const MAX_DEAD_LETTERS: usize = 1000;
+
+/// The webhooks and the events that could not be delivered.
+#[derive(Debug, Default)]
+pub struct Webhooks {
+    /// The webhooks, with their secret.
+    subscriptions: IndexMap<usize, (Webhook, Option<String>)>,
+    /// The ID of the last created webhook, never reused.
+    last_id: usize,
+    /// The events that could not be delivered, the oldest first.
+    dead_letters: VecDeque<DeadLetter>,
+}
+
+/// An event to deliver to a webhook.
+#[derive(Debug)]
+struct Delivery {
+    /// The ID of the webhook.
+    webhook_id: usize,
+    /// The event to deliver.
+    event: NotificationEvent,
+    /// The number of attempts already made.
+    attempts: u32,
+}
+
+/// Returns the routes to manage the webhooks.
+pub fn routes() -> Router<Arc<Mutex<AppState>>> {
+    Router::new()
+        .route("/webhooks", get(list_webhooks).post(create_webhook))
+        .route(
+            "/webhooks/dead-letters",
+            get(list_dead_letters).delete(clear_dead_letters),
+        )
+        .route(
+            "/webhooks/{id}",
+            get(get_webhook).put(update_webhook).delete(delete_webhook),
+        )
+}
+
+/// Forwards the notification events to the webhooks, as long as the service
+/// runs.
+pub async fn run(state: Arc<Mutex<AppState>>) {
+    let (sender, receiver) = mpsc::channel(QUEUE_CAPACITY);
+    let events = state.lock().expect("poisoned lock").events.subscribe();
+
+    tokio::join!(
+        enqueue(Arc::clone(&state), events, sender.clone()),
+        deliver(state, sender, receiver),
+    );
+}
+
+impl Webhooks {
+    /// Returns the IDs of the webhooks subscribed to a kind of event.
+    fn subscribed(&self, kind: EventKind) -> Vec<usize> {
+        self.subscriptions
+            .values()
+            .filter(|(webhook, _)| {
+                webhook.events.is_empty() || webhook.events.contains(&kind)
+            })
+            .map(|(webhook, _)| webhook.id)
+            .collect()
+    }
+
+    /// Adds a dead letter, dropping the oldest ones above
+    /// [`MAX_DEAD_LETTERS`].
+    fn add_dead_letter(&mut self, dead_letter: DeadLetter) {
+        if self.dead_letters.len() >= MAX_DEAD_LETTERS {
+            self.dead_letters.pop_front();
+        }
+
+        self.dead_letters.push_back(dead_letter);
+    }
+}
+
+/// Queues a delivery for each event and matching webhook.
+///
+/// When the queue is full, waiting here makes the events lag, so that the
+/// oldest ones are dropped instead of growing the queue.
+async fn enqueue(
+    state: Arc<Mutex<AppState>>,
+    mut events: tokio::sync::broadcast::Receiver<NotificationEvent>,
+    sender: mpsc::Sender<Delivery>,
+) {
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(count)) => {
+                tracing::warn!(count, "some events have not been forwarded");
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+
+        let webhook_ids = state
+            .lock()
+            .expect("poisoned lock")
+            .webhooks
+            .subscribed(event.kind());
+
+        for webhook_id in webhook_ids {
+            let delivery = Delivery {
+                webhook_id,
+                event: event.clone(),
+                attempts: 0,
+            };
+
+            if sender.send(delivery).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Delivers the queued events, with at most [`MAX_CONCURRENT_ATTEMPTS`]
+/// attempts in progress.
+async fn deliver(
+    state: Arc<Mutex<AppState>>,
+    sender: mpsc::Sender<Delivery>,
+    mut receiver: mpsc::Receiver<Delivery>,
+) {
+    let attempts = Arc::new(Semaphore::new(MAX_CONCURRENT_ATTEMPTS));
+
+    while let Some(delivery) = receiver.recv().await {
+        let permit = Arc::clone(&attempts)
+            .acquire_owned()
+            .await
+            .expect("the semaphore is never closed");
+
+        tokio::spawn(attempt(
+            Arc::clone(&state),
+            sender.clone(),
+            delivery,
+            permit,
+        ));
+    }
+}
+
+/// Attempts to deliver an event, then queues it again after a delay or moves
+/// it to the dead letters if it has failed.
+async fn attempt(
+    state: Arc<Mutex<AppState>>,
+    sender: mpsc::Sender<Delivery>,
+    mut delivery: Delivery,
+    permit: OwnedSemaphorePermit,
+) {
+    let subscription = {
+        let state = state.lock().expect("poisoned lock");
+        state.webhooks.subscriptions.get(&delivery.webhook_id).map(
+            |(webhook, secret)| {
+                (webhook.url.clone(), secret.clone(), state.http.clone())
+            },
+        )
+    };
+
+    // NOTE: The webhook may have been deleted in the meantime.
+    let Some((url, secret, http)) = subscription else {
+        return;
+    };
+
+    delivery.attempts += 1;
+    let result = send(&http, &url, secret.as_deref(), &delivery.event).await;
+    drop(permit);
+
+    let Err(error) = result else {
+        return;
+    };
+
+    tracing::warn!(
+        url,
+        attempts = delivery.attempts,
+        error,
+        "failed to deliver an event"
+    );
+
+    if delivery.attempts < MAX_ATTEMPTS {
+        tokio::time::sleep(RETRY_DELAY * 2_u32.pow(delivery.attempts - 1))
+            .await;
+
+        if sender.send(delivery).await.is_err() {
+            tracing::warn!("the delivery queue is closed");
+        }
+    } else {
+        state
+            .lock()
+            .expect("poisoned lock")
+            .webhooks
+            .add_dead_letter(DeadLetter {
+                webhook_id: delivery.webhook_id,
+                event: delivery.event,
+                attempts: delivery.attempts,
+                error,
+                failed_at: Utc::now(),
+            });
+    }
+}
+
+/// Sends an event to a webhook.
+async fn send(
+    http: &HttpClient,
+    url: &str,
+    secret: Option<&str>,
+    event: &NotificationEvent,
+) -> Result<(), String> {
+    let body = serde_json::to_vec(event).map_err(|error| error.to_string())?;
+
+    let mut request = http
+        .post(url)?
+        .header("Content-Type", "application/json")
+        .header("X-Ntf-Event", kind_name(event));
+
+    if let Some(secret) = secret {
+        request = request.header("X-Ntf-Signature", sign(secret, &body));
+    }
+
+    let response = request
+        .body(body)
+        .send()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("HTTP status {}", response.status()))
+    }
+}
+
+/// Returns the name of the kind of an event, as in its JSON.
+fn kind_name(event: &NotificationEvent) -> String {
+    serde_json::to_value(event.kind())
+        .ok()
+        .and_then(|kind| kind.as_str().map(str::to_owned))
+        .unwrap_or_default()
+}
+
+/// Signs a body with HMAC-SHA256, as `sha256=<hex>`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any size");
+    mac.update(body);
+
+    mac.finalize().into_bytes().iter().fold(
+        String::from("sha256="),
+        |mut signature, byte| {
+            // NOTE: Writing to a string cannot fail.
+            let _ignored = write!(signature, "{byte:02x}");
+            signature
+        },
+    )
+}
+
+/// Lists the webhooks.
+#[tracing::instrument(skip(state))]
+async fn list_webhooks(
+    State(state): State<Arc<Mutex<AppState>>>,
+) -> Json<Vec<Webhook>> {
+    let webhooks = state
+        .lock()
+        .expect("poisoned lock")
+        .webhooks
+        .subscriptions
+        .values()
+        .map(|(webhook, _)| webhook.clone())
+        .collect();
+
+    tracing::info!(?webhooks, "LIST WEBHOOKS");
+    Json(webhooks)
+}
+
+/// Creates a webhook.
+#[tracing::instrument(skip(state))]
+async fn create_webhook(
+    State(state): State<Arc<Mutex<AppState>>>,
+    WithRejection(Json(payload), _): WithRejection<
+        Json<WebhookPayload>,
+        CreateNotificationError,
+    >,
+) -> Result<Webhook, CreateNotificationError> {
+    validate(&payload).log_err()?;
+
+    let mut state = state.lock().expect("poisoned lock");
+    let webhooks = &mut state.webhooks;
+    webhooks.last_id += 1;
+    let id = webhooks.last_id;
+    let webhook =
+        webhook(id, payload.url, payload.events, payload.secret.is_some());
+    webhooks
+        .subscriptions
+        .insert(id, (webhook.clone(), payload.secret));
+
+    tracing::info!(?webhook, "CREATE WEBHOOK");
+    Ok(webhook)
+}
+
+/// Gets a webhook by its ID.
+#[tracing::instrument(skip(state))]
+async fn get_webhook(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path(id): Path<usize>,
+) -> Result<Webhook, ResourceError> {
+    let webhook = state
+        .lock()
+        .expect("poisoned lock")
+        .webhooks
+        .subscriptions
+        .get(&id)
+        .map(|(webhook, _)| webhook.clone())
+        .ok_or(ResourceError::NotFound { id })
+        .log_err()?;
+
+    tracing::info!(?webhook, "GET WEBHOOK");
+    Ok(webhook)
+}
+
+/// Replaces a webhook.
+#[tracing::instrument(skip(state))]
+async fn update_webhook(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path(id): Path<usize>,
+    WithRejection(Json(payload), _): WithRejection<
+        Json<WebhookPayload>,
+        CreateNotificationError,
+    >,
+) -> Result<Result<Webhook, ResourceError>, CreateNotificationError> {
+    validate(&payload).log_err()?;
+
+    let mut state = state.lock().expect("poisoned lock");
+    let Some(subscription) = state.webhooks.subscriptions.get_mut(&id) else {
+        return Ok(Err(ResourceError::NotFound { id }).log_err());
+    };
+
+    let webhook =
+        webhook(id, payload.url, payload.events, payload.secret.is_some());
+    *subscription = (webhook.clone(), payload.secret);
+
+    tracing::info!(?webhook, "UPDATE WEBHOOK");
+    Ok(Ok(webhook))
+}
+
+/// Deletes a webhook.
+#[tracing::instrument(skip(state))]
+async fn delete_webhook(
+    State(state): State<Arc<Mutex<AppState>>>,
+    Path(id): Path<usize>,
+) -> Result<Webhook, ResourceError> {
+    let (webhook, _) = state
+        .lock()
+        .expect("poisoned lock")
+        .webhooks
+        .subscriptions
+        .shift_remove(&id)
+        .ok_or(ResourceError::NotFound { id })
+        .log_err()?;
+
+    tracing::info!(?webhook, "DELETE WEBHOOK");
+    Ok(webhook)
+}
+
+/// Lists the events that could not be delivered.
+#[tracing::instrument(skip(state))]
+async fn list_dead_letters(
+    State(state): State<Arc<Mutex<AppState>>>,
+) -> Json<Vec<DeadLetter>> {
+    let dead_letters = state
+        .lock()
+        .expect("poisoned lock")
+        .webhooks
+        .dead_letters
+        .iter()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    tracing::info!(count = dead_letters.len(), "LIST DEAD LETTERS");
+    Json(dead_letters)
+}
+
+/// Clears the events that could not be delivered, and returns them.
+#[tracing::instrument(skip(state))]
+async fn clear_dead_letters(
+    State(state): State<Arc<Mutex<AppState>>>,
+) -> Json<Vec<DeadLetter>> {
+    let dead_letters = Vec::from(std::mem::take(
+        &mut state.lock().expect("poisoned lock").webhooks.dead_letters,
+    ));
+
+    tracing::info!(count = dead_letters.len(), "CLEAR DEAD LETTERS");
+    Json(dead_letters)
+}
+
+/// Checks the payload of a webhook.
+fn validate(payload: &WebhookPayload) -> Result<(), CreateNotificationError> {
+    let mut validator = Validator::default();
+    validator.url("url", &payload.url);
+    validator.finish()
+}
+
+/// Builds a webhook, as returned by the API.
+fn webhook(
+    id: usize,
+    url: String,
+    events: Vec<EventKind>,
+    signed: bool,
+) -> Webhook {
+    Webhook {
+        id,
+        url,
+        events,
+        signed,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #![expect(clippy::missing_panics_doc, reason = "tests")]
+
+    use std::marker::PhantomData;
+
+    use ntf_api_types::Priority;
+
+    use crate::{ingest, outbound::HttpClient};
+
+    use super::*;
+
+    fn payload(
+        url: &str,
+    ) -> WithRejection<Json<WebhookPayload>, CreateNotificationError> {
+        WithRejection(
+            Json(WebhookPayload {
+                url: url.to_owned(),
+                events: Vec::new(),
+                secret: None,
+            }),
+            PhantomData,
+        )
+    }
+
+    #[test]
+    fn subscribed_filters_by_event_kind() {
+        let mut webhooks = Webhooks::default();
+        for (id, events) in [
+            (1, vec![EventKind::Created]),
+            (2, vec![EventKind::Acked, EventKind::Deleted]),
+            (3, Vec::new()),
+        ] {
+            let webhook = webhook(id, String::new(), events, false);
+            webhooks.subscriptions.insert(id, (webhook, None));
+        }
+
+        assert_eq!(webhooks.subscribed(EventKind::Created), [1, 3]);
+        assert_eq!(webhooks.subscribed(EventKind::Deleted), [2, 3]);
+        assert_eq!(webhooks.subscribed(EventKind::Restored), [3]);
+    }
+
+    #[tokio::test]
+    async fn attempt_retries_then_moves_to_the_dead_letters() {
+        let state = Arc::new(Mutex::new(AppState {
+            http: HttpClient::new(vec![String::from("127.0.0.1")]),
+            ..AppState::default()
+        }));
+
+        // NOTE: Nothing listens on the port once the listener is dropped.
+        let listener =
+            tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        drop(listener);
+
+        let event = {
+            let mut state = state.lock().unwrap();
+            let webhook = webhook(1, url, Vec::new(), false);
+            state.webhooks.subscriptions.insert(1, (webhook, None));
+            NotificationEvent::Created(state.create(ingest::payload(
+                "Disk full",
+                "",
+                Priority::High,
+                Vec::new(),
+                "",
+            )))
+        };
+
+        let (sender, mut receiver) = mpsc::channel(1);
+        let semaphore = Arc::new(Semaphore::new(1));
+        let permit = || Arc::clone(&semaphore).try_acquire_owned().unwrap();
+
+        let delivery = Delivery {
+            webhook_id: 1,
+            event,
+            attempts: 0,
+        };
+        attempt(Arc::clone(&state), sender.clone(), delivery, permit()).await;
+        let retry = receiver.try_recv().unwrap();
+        assert_eq!(retry.attempts, 1);
+        assert!(
+            state.lock().unwrap().webhooks.dead_letters.is_empty(),
+            "the delivery is retried"
+        );
+
+        let delivery = Delivery {
+            attempts: MAX_ATTEMPTS - 1,
+            ..retry
+        };
+        attempt(Arc::clone(&state), sender, delivery, permit()).await;
+        receiver.try_recv().unwrap_err();
+
+        let state = state.lock().unwrap();
+        let dead_letter = state.webhooks.dead_letters.front().unwrap();
+        assert_eq!(dead_letter.webhook_id, 1);
+        assert_eq!(dead_letter.attempts, MAX_ATTEMPTS);
+    }
+
+    #[tokio::test]
+    async fn webhooks_are_managed_with_unique_ids() {
+        let state = Arc::new(Mutex::new(AppState::default()));
+        let state = || State(Arc::clone(&state));
+
+        let first = create_webhook(state(), payload("https://a.example/"))
+            .await
+            .unwrap();
+        let second = create_webhook(state(), payload("https://b.example/"))
+            .await
+            .unwrap();
+        delete_webhook(state(), Path(second.id)).await.unwrap();
+        let third = create_webhook(state(), payload("https://c.example/"))
+            .await
+            .unwrap();
+        assert_eq!([first.id, second.id, third.id], [1, 2, 3]);
+
+        let updated =
+            update_webhook(state(), Path(1), payload("https://d.example/"))
+                .await
+                .unwrap()
+                .unwrap();
+        assert_eq!(updated.url, "https://d.example/");
+        assert_eq!(
+            get_webhook(state(), Path(1)).await.unwrap().url,
+            updated.url
+        );
+
+        get_webhook(state(), Path(2)).await.unwrap_err();
+        update_webhook(state(), Path(2), payload("https://e.example/"))
+            .await
+            .unwrap()
+            .unwrap_err();
+        create_webhook(state(), payload("ftp://f.example/"))
+            .await
+            .unwrap_err();
+
+        let Json(webhooks) = list_webhooks(state()).await;
+        let ids = webhooks
+            .iter()
+            .map(|webhook| webhook.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [1, 3]);
+    }
+
+    #[test]
+    fn sign_computes_the_hmac_sha256() {
+        // NOTE: Test case 2 of RFC 4231.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c7\
+                5a003f089d2739839dec58b964ec3843"
+        );
+    }
+}